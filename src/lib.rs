@@ -0,0 +1,68 @@
+//! A simple library for solving sudoku puzzles.
+//!
+//! A puzzle is read into a [`SudokuGrid`], either from text in one of the [`GridFormat`]s (a
+//! file, any reader, or a string) or by entering values one at a time, and then solved with
+//! [`SudokuGrid::solve_grid`]. The cells of a grid, and the values that are still possible for
+//! them, can be inspected through [`SudokuCell`] and [`SudokuValues`]. Anything that goes
+//! wrong is reported as a [`SudokuError`].
+//!
+//! Solving engines implement [`SudokuSolver`]. The [`BacktrackingSolver`] deduces what it can
+//! and guesses the rest, with a choice of [`BranchStrategy`] for what to guess. The
+//! [`DancingLinksSolver`] treats the grid as an exact cover problem instead.
+//!
+//! Before guessing, the [`BacktrackingSolver`] applies whichever [`TechniqueSet`] it was given.
+//! [`SudokuGrid::find_deduction`] finds the next step of logic on its own, as a [`Deduction`]
+//! that records the [`Pattern`] found and the [`Candidate`]s it rules out.
+//!
+//! The library never prints anything. To follow along with a solve, hand a
+//! [`SolverObserver`] to [`BacktrackingSolver::solve_observed`]; it is told about every
+//! [`SolverEvent`] as it happens. [`BacktrackingSolver::solve_with_stats`] uses one to
+//! gather [`SolveStats`], for comparing how hard puzzles are.
+//!
+//! [`SudokuGrid::solutions`] iterates over every solution of a puzzle,
+//! [`SudokuGrid::count_solutions`] and [`SudokuGrid::is_unique`] tell whether a puzzle is
+//! proper, and [`SudokuGrid::generate`] makes new puzzles that are.
+//!
+//! Files holding many puzzles can be streamed through [`PuzzleReader`], or solved in one go with
+//! [`solve_batch`].
+#![allow(clippy::needless_return)]
+
+mod sudoku_als;
+mod sudoku_batch;
+mod sudoku_cell;
+mod sudoku_chains;
+mod sudoku_coloring;
+mod sudoku_deduction;
+mod sudoku_dlx;
+mod sudoku_error;
+mod sudoku_fish;
+mod sudoku_format;
+mod sudoku_generator;
+mod sudoku_grid;
+mod sudoku_intersections;
+mod sudoku_observer;
+mod sudoku_single_digit;
+mod sudoku_solutions;
+mod sudoku_solver;
+mod sudoku_stats;
+mod sudoku_subsets;
+mod sudoku_uniqueness;
+mod sudoku_units;
+mod sudoku_values;
+mod sudoku_wings;
+
+pub use sudoku_batch::{solve_batch, BatchPuzzle, BatchSummary, PuzzleReader};
+pub use sudoku_cell::SudokuCell;
+pub use sudoku_deduction::{
+    AlmostLockedSet, Candidate, ChainNode, Deduction, Pattern, StrongLink, Technique, TechniqueSet,
+    Unit,
+};
+pub use sudoku_dlx::DancingLinksSolver;
+pub use sudoku_error::SudokuError;
+pub use sudoku_format::GridFormat;
+pub use sudoku_grid::SudokuGrid;
+pub use sudoku_observer::{NoObserver, SolverEvent, SolverObserver};
+pub use sudoku_solutions::Solutions;
+pub use sudoku_solver::{BacktrackingSolver, BranchStrategy, SudokuSolver};
+pub use sudoku_stats::SolveStats;
+pub use sudoku_values::{SudokuValues, SudokuValuesIter};
@@ -1,53 +1,599 @@
-mod sudoku_cell;
-mod sudoku_grid;
-mod sudoku_values;
+#![allow(clippy::needless_return)]
 
+use std::env;
 use std::fs::File;
-use std::io;
-use sudoku_grid::SudokuGrid;
-use std::path::Path;
-
-fn main() -> io::Result<()> {
-    println!("{0}", "Please enter the puzzle's path");
-
-    loop {
-        match read_grid() {
-            Ok(grid) => {
-                match grid.solve_grid() {
-                    Ok(g) => {
-                        println!("{:?}", g);
-                        break;
-                    }
-                    Err(e) => {
-                        println!("{:?}", e);
-                        break;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sudoku::{
+    solve_batch, BacktrackingSolver, BatchSummary, DancingLinksSolver, GridFormat, PuzzleReader,
+    SudokuError, SudokuGrid, SudokuSolver, TechniqueSet,
+};
+
+const EXIT_SOLVED: i32 = 0;
+const EXIT_UNSOLVABLE: i32 = 1;
+const EXIT_INVALID_INPUT: i32 = 2;
+//Same value as EX_USAGE from sysexits.h
+const EXIT_USAGE: i32 = 64;
+
+const USAGE: &str = "\
+Usage: sudoku <command> [options] [FILE...]
+
+Commands:
+  solve    Solve every puzzle in the input
+  check    Make sure every puzzle in the input is well formed
+  hint     Reveal the value of one empty cell for every puzzle in the input
+  count    Count the solutions of every puzzle in the input
+  rate     Show what it took to solve every puzzle in the input: values placed by each
+           technique, guesses, backtracks, search depth, grid copies and time taken.
+           Always uses the backtracking engine.
+  generate Make new puzzles that have exactly one solution
+  help     Show this message
+
+Each FILE can hold any number of puzzles, either as single lines of 81 characters or as
+comma delimited grids separated by blank lines. Use '-', or no FILE at all, to read from stdin.
+
+Options:
+  -o, --output <FILE>          Write to FILE instead of stdout
+  -f, --format <csv|line>      Write solutions in this format instead of the input's
+  -l, --limit <N>              Stop counting solutions after N of them (default 1000)
+  -n, --number <N>             Number of puzzles to generate (default 1)
+  -s, --seed <N>               Seed for generating puzzles, the same seed gives the same puzzles
+  -e, --engine <backtracking|dlx>
+                               Solve with deduction and backtracking (the default), or with
+                               Dancing Links
+  -u, --unique                 Trust that every puzzle has exactly one solution, and use the
+                               techniques that rely on it (unique rectangles and BUG+1) when
+                               solving. Counting never uses them.
+
+Exit codes:
+  0   every puzzle was solved (or is valid, for check)
+  1   at least one puzzle has no solution
+  2   at least one puzzle could not be read
+  64  the command line was not understood";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    process::exit(run(&args));
+}
+
+fn run(args: &[String]) -> i32 {
+    let (command, rest) = match args.split_first() {
+        Some(split) => split,
+        None => {
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    if command == "help" || command == "--help" || command == "-h" {
+        println!("{}", USAGE);
+        return EXIT_SOLVED;
+    }
+
+    let options = match Options::parse(rest) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    let mut output = match options.open_output() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Failed to create the output file: {}", e);
+            return EXIT_INVALID_INPUT;
+        }
+    };
+
+    let result = match command.as_str() {
+        "solve" => solve(&options, &mut output),
+        "check" => check(&options, &mut output),
+        "hint" => hint(&options, &mut output),
+        "count" => count(&options, &mut output),
+        "rate" => rate(&options, &mut output),
+        "generate" => generate(&options, &mut output),
+        _ => {
+            eprintln!("Unknown command '{}'\n\n{}", command, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    return match result.and_then(|code| output.flush().map(|_| code)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Failed to write the results: {}", e);
+            EXIT_INVALID_INPUT
+        }
+    };
+}
+
+///Everything after the command
+struct Options {
+    inputs: Vec<String>,
+    output: Option<String>,
+    format: Option<GridFormat>,
+    limit: usize,
+    number: usize,
+    seed: Option<u64>,
+    unique: bool,
+    solver: Box<dyn SudokuSolver>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            inputs: Vec::new(),
+            output: None,
+            format: None,
+            limit: 1000,
+            number: 1,
+            seed: None,
+            unique: false,
+            solver: Box::new(BacktrackingSolver::new()),
+        };
+        let mut dlx = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-o" | "--output" => match args.next() {
+                    Some(path) => options.output = Some(path.clone()),
+                    None => return Err(format!("{} needs a file", arg)),
+                },
+                "-f" | "--format" => match args.next().map(|f| f.as_str()) {
+                    Some("csv") => options.format = Some(GridFormat::Csv),
+                    Some("line") => options.format = Some(GridFormat::Line),
+                    Some(f) => return Err(format!("Unknown format '{}'", f)),
+                    None => return Err(format!("{} needs a format", arg)),
+                },
+                "-l" | "--limit" => options.limit = parse_number(arg, args.next())?,
+                "-n" | "--number" => options.number = parse_number(arg, args.next())?,
+                "-s" | "--seed" => options.seed = Some(parse_number(arg, args.next())?),
+                "-e" | "--engine" => match args.next().map(|e| e.as_str()) {
+                    Some("backtracking") => dlx = false,
+                    Some("dlx") => dlx = true,
+                    Some(e) => return Err(format!("Unknown engine '{}'", e)),
+                    None => return Err(format!("{} needs an engine", arg)),
+                },
+                "-u" | "--unique" => options.unique = true,
+                "-" => options.inputs.push(arg.clone()),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+                _ => options.inputs.push(arg.clone()),
+            }
+        }
+        if options.inputs.is_empty() {
+            options.inputs.push("-".to_string());
+        }
+        if dlx {
+            options.solver = Box::new(DancingLinksSolver::new());
+        } else {
+            options.solver = Box::new(options.backtracking_solver(TechniqueSet::default()));
+        }
+        return Ok(options);
+    }
+
+    fn backtracking_solver(&self, techniques: TechniqueSet) -> BacktrackingSolver {
+        return BacktrackingSolver::new()
+            .with_techniques(techniques)
+            .assume_unique_solution(self.unique);
+    }
+
+    fn open_output(&self) -> io::Result<Box<dyn Write>> {
+        return Ok(match &self.output {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(BufWriter::new(io::stdout())),
+        });
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<&String>) -> Result<T, String> {
+    return match value {
+        Some(v) => v
+            .parse::<T>()
+            .map_err(|_| format!("{} needs a number, not '{}'", option, v)),
+        None => Err(format!("{} needs a number", option)),
+    };
+}
+
+///Opens a file, or stdin for '-'. A file that can't be opened is reported and counted as
+///invalid input, without stopping the other files from being read.
+fn open_input(path: &str) -> Option<Box<dyn BufRead>> {
+    if path == "-" {
+        return Some(Box::new(BufReader::new(io::stdin())));
+    }
+    return match File::open(path) {
+        Ok(f) => Some(Box::new(BufReader::new(f))),
+        Err(e) => {
+            eprintln!("{}: {}", path, SudokuError::Io(e));
+            None
+        }
+    };
+}
+
+fn solve(options: &Options, output: &mut dyn Write) -> io::Result<i32> {
+    let mut summary = BatchSummary::default();
+    let mut code = EXIT_SOLVED;
+
+    for path in &options.inputs {
+        let input = match open_input(path) {
+            Some(i) => i,
+            None => {
+                code = EXIT_INVALID_INPUT;
+                continue;
+            }
+        };
+        let file_summary = solve_batch(input, output, options.format, options.solver.as_ref())?;
+        summary.solved += file_summary.solved;
+        summary.unsolvable += file_summary.unsolvable;
+        summary.invalid += file_summary.invalid;
+    }
+
+    if summary.total() > 1 {
+        eprintln!(
+            "{} puzzles: {} solved, {} unsolvable, {} invalid",
+            summary.total(),
+            summary.solved,
+            summary.unsolvable,
+            summary.invalid
+        );
+    }
+    if summary.invalid > 0 {
+        code = EXIT_INVALID_INPUT;
+    } else if summary.unsolvable > 0 {
+        code = code.max(EXIT_UNSOLVABLE);
+    }
+    return Ok(code);
+}
+
+fn check(options: &Options, output: &mut dyn Write) -> io::Result<i32> {
+    let mut code = EXIT_SOLVED;
+
+    for path in &options.inputs {
+        let input = match open_input(path) {
+            Some(i) => i,
+            None => {
+                code = EXIT_INVALID_INPUT;
+                continue;
+            }
+        };
+        for puzzle in PuzzleReader::new(input) {
+            match puzzle.grid {
+                Ok(grid) => {
+                    let givens = grid.cells().iter().filter(|c| c.value().is_some()).count();
+                    let state = if grid.is_solved() {
+                        "complete"
+                    } else {
+                        "incomplete"
+                    };
+                    writeln!(
+                        output,
+                        "Puzzle {}: valid, {} givens, {}",
+                        puzzle.number, givens, state
+                    )?;
+                }
+                Err(e) => {
+                    writeln!(
+                        output,
+                        "Puzzle {} (line {}): {}",
+                        puzzle.number,
+                        puzzle.line + 1,
+                        e
+                    )?;
+                    code = code.max(exit_code(&e));
+                }
+            }
+        }
+    }
+    return Ok(code);
+}
+
+fn hint(options: &Options, output: &mut dyn Write) -> io::Result<i32> {
+    let mut code = EXIT_SOLVED;
+
+    for path in &options.inputs {
+        let input = match open_input(path) {
+            Some(i) => i,
+            None => {
+                code = EXIT_INVALID_INPUT;
+                continue;
+            }
+        };
+        for puzzle in PuzzleReader::new(input) {
+            let result = puzzle
+                .grid
+                .and_then(|grid| Ok((options.solver.solve(&grid)?, grid)));
+            match result {
+                Ok((solved, grid)) => {
+                    match grid.cells().iter().find(|c| c.value().is_none()) {
+                        Some(cell) => writeln!(
+                            output,
+                            "Puzzle {}: Row {} Column {} is {}",
+                            puzzle.number,
+                            cell.row() + 1,
+                            cell.col() + 1,
+                            solved.get_value(cell.row(), cell.col()).unwrap_or(0)
+                        )?,
+                        None => writeln!(output, "Puzzle {}: already solved", puzzle.number)?,
+                    };
+                }
+                Err(e) => {
+                    writeln!(
+                        output,
+                        "Puzzle {} (line {}): {}",
+                        puzzle.number,
+                        puzzle.line + 1,
+                        e
+                    )?;
+                    code = code.max(exit_code(&e));
+                }
+            }
+        }
+    }
+    return Ok(code);
+}
+
+fn count(options: &Options, output: &mut dyn Write) -> io::Result<i32> {
+    let mut code = EXIT_SOLVED;
+
+    for path in &options.inputs {
+        let input = match open_input(path) {
+            Some(i) => i,
+            None => {
+                code = EXIT_INVALID_INPUT;
+                continue;
+            }
+        };
+        for puzzle in PuzzleReader::new(input) {
+            match puzzle.grid {
+                Ok(grid) => {
+                    let solutions = options.solver.count_solutions(&grid, options.limit);
+                    let qualifier = if solutions >= options.limit {
+                        "at least "
+                    } else {
+                        ""
+                    };
+                    writeln!(
+                        output,
+                        "Puzzle {}: {}{} solution{}",
+                        puzzle.number,
+                        qualifier,
+                        solutions,
+                        if solutions == 1 { "" } else { "s" }
+                    )?;
+                    if solutions == 0 {
+                        code = code.max(EXIT_UNSOLVABLE);
                     }
                 }
+                Err(e) => {
+                    writeln!(
+                        output,
+                        "Puzzle {} (line {}): {}",
+                        puzzle.number,
+                        puzzle.line + 1,
+                        e
+                    )?;
+                    code = code.max(exit_code(&e));
+                }
             }
-            Err(e) => {
-                println!("{:?}", e);
+        }
+    }
+    return Ok(code);
+}
+
+fn rate(options: &Options, output: &mut dyn Write) -> io::Result<i32> {
+    let mut code = EXIT_SOLVED;
+    //Rating is about the steps it takes, so use every technique there is
+    let solver = options.backtracking_solver(TechniqueSet::safe());
+
+    for path in &options.inputs {
+        let input = match open_input(path) {
+            Some(i) => i,
+            None => {
+                code = EXIT_INVALID_INPUT;
+                continue;
             }
+        };
+        for puzzle in PuzzleReader::new(input) {
+            let result = puzzle.grid.and_then(|grid| {
+                let (solved, stats) = solver.solve_with_stats(&grid);
+                solved.map(|_| stats)
+            });
+            match result {
+                Ok(stats) => writeln!(output, "Puzzle {}: {}", puzzle.number, stats)?,
+                Err(e) => {
+                    writeln!(
+                        output,
+                        "Puzzle {} (line {}): {}",
+                        puzzle.number,
+                        puzzle.line + 1,
+                        e
+                    )?;
+                    code = code.max(exit_code(&e));
+                }
+            }
+        }
+    }
+    return Ok(code);
+}
+
+fn generate(options: &Options, output: &mut dyn Write) -> io::Result<i32> {
+    let seed = match options.seed {
+        Some(s) => s,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0),
+    };
+    let format = options.format.unwrap_or(GridFormat::Csv);
+
+    for i in 0..options.number {
+        let puzzle = SudokuGrid::generate(seed.wrapping_add(i as u64));
+        match format {
+            GridFormat::Line => writeln!(output, "{}", puzzle.to_line())?,
+            GridFormat::Csv => writeln!(output, "{}", puzzle.to_csv())?,
         }
     }
+    return Ok(EXIT_SOLVED);
+}
 
-    Ok(())
+///Puzzles that can't be solved and puzzles that couldn't be read get different exit codes,
+///so scripts can tell them apart
+fn exit_code(error: &SudokuError) -> i32 {
+    return if error.is_unsolvable() {
+        EXIT_UNSOLVABLE
+    } else {
+        EXIT_INVALID_INPUT
+    };
 }
 
-fn read_grid() -> Result<SudokuGrid, String> {
-    let mut input = String::new();
-    let input_result = io::stdin().read_line(&mut input);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const PUZZLE: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    const SOLVED: &str =
+        "561932874297458613843761259958174362726385491134629785485296137319847526672513948";
+    //r1c1 given as 2, which leaves the puzzle without a solution
+    const UNSOLVABLE: &str =
+        "261...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+
+    ///A file in the temporary directory, named after the test so tests running at the same
+    ///time don't share one
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("sudoku-{}-{}", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        return path;
+    }
+
+    ///Runs the command on the given input, returning the exit code and what it wrote
+    fn run_on(name: &str, command: &[&str], input: &str) -> (i32, String) {
+        let input = temp_file(&format!("{}.in", name), input);
+        let output = temp_file(&format!("{}.out", name), "");
+        let mut args = command
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>();
+        args.push(input.to_string_lossy().to_string());
+        args.push("-o".to_string());
+        args.push(output.to_string_lossy().to_string());
 
-    if let Err(_) = input_result {
-        return Err("Failed to read input".to_string());
+        let code = run(&args);
+        let written = fs::read_to_string(&output).unwrap();
+        fs::remove_file(input).unwrap();
+        fs::remove_file(output).unwrap();
+        return (code, written);
     }
-    let path = Path::new(input.trim());
 
-    let file = File::open(path);
-    if let Err(_) = file {
-        return Err("Invalid file".to_string());
+    #[test]
+    fn solve_writes_solutions() {
+        assert_eq!(
+            run_on("solve", &["solve"], PUZZLE),
+            (EXIT_SOLVED, format!("{}\n", SOLVED))
+        );
+        let (code, csv) = run_on("solve-csv", &["solve", "-f", "csv"], PUZZLE);
+        assert_eq!(code, EXIT_SOLVED);
+        assert_eq!(csv.lines().next(), Some("5,6,1,9,3,2,8,7,4"));
+        let (code, _) = run_on("solve-dlx", &["solve", "-e", "dlx"], PUZZLE);
+        assert_eq!(code, EXIT_SOLVED);
     }
 
-    let final_file = file.unwrap();
+    #[test]
+    fn exit_codes_tell_failures_apart() {
+        let (code, _) = run_on("unsolvable", &["solve"], UNSOLVABLE);
+        assert_eq!(code, EXIT_UNSOLVABLE);
+        let both = format!("{}\n{}\n", UNSOLVABLE, &PUZZLE[1..]);
+        let (code, output) = run_on("invalid", &["solve"], &both);
+        assert_eq!(code, EXIT_INVALID_INPUT);
+        assert_eq!(
+            output.lines().nth(1),
+            Some("Puzzle 2 (line 2): A single line puzzle must have 81 characters, found 80")
+        );
+        let missing = vec!["check".to_string(), "no-such-file.txt".to_string()];
+        assert_eq!(run(&missing), EXIT_INVALID_INPUT);
+    }
+
+    #[test]
+    fn check_and_hint() {
+        assert_eq!(
+            run_on("check", &["check"], PUZZLE),
+            (
+                EXIT_SOLVED,
+                "Puzzle 1: valid, 34 givens, incomplete\n".to_string()
+            )
+        );
+        assert_eq!(
+            run_on("hint", &["hint"], PUZZLE),
+            (EXIT_SOLVED, "Puzzle 1: Row 1 Column 1 is 5\n".to_string())
+        );
+    }
+
+    #[test]
+    fn count_shows_how_many_solutions() {
+        //The puzzle with a few givens taken out has three solutions
+        let several =
+            ".61...8......5...384.76..5..5..7.36.7..3.5..1..4.2.....8..96.3.3...475...72......";
+        let input = format!("{}\n{}\n{}\n", PUZZLE, several, UNSOLVABLE);
+        assert_eq!(
+            run_on("count", &["count"], &input),
+            (
+                EXIT_UNSOLVABLE,
+                "Puzzle 1: 1 solution\nPuzzle 2: 3 solutions\nPuzzle 3: 0 solutions\n".to_string()
+            )
+        );
+        let (code, output) = run_on("count-limit", &["count", "-l", "2"], several);
+        assert_eq!(code, EXIT_SOLVED);
+        assert_eq!(output, "Puzzle 1: at least 2 solutions\n");
+    }
+
+    #[test]
+    fn rate_shows_the_solve_stats() {
+        let input = format!("{}\n{}\n", PUZZLE, UNSOLVABLE);
+        let (code, output) = run_on("rate", &["rate"], &input);
+        assert_eq!(code, EXIT_UNSOLVABLE);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        //Everything but the time, which changes from run to run
+        assert!(lines[0].starts_with(
+            "Puzzle 1: naked single: 35, hidden single: 12, eliminations: 67, guesses: 0, \
+             backtracks: 0, depth: 0, clones: 0, time: "
+        ));
+        assert_eq!(
+            lines[1],
+            "Puzzle 2 (line 2): Row 2 Column 1 has no possible values, the puzzle has no solution"
+        );
+    }
+
+    #[test]
+    fn generate_makes_proper_puzzles() {
+        let (code, output) = run_on(
+            "generate",
+            &["generate", "-n", "2", "-s", "1", "-f", "line"],
+            "",
+        );
+        assert_eq!(code, EXIT_SOLVED);
+        let puzzles = output.lines().collect::<Vec<&str>>();
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0], SudokuGrid::generate(1).to_line());
+        assert_eq!(puzzles[1], SudokuGrid::generate(2).to_line());
+    }
 
-    return SudokuGrid::parse_grid(final_file);
-}
\ No newline at end of file
+    #[test]
+    fn bad_command_lines_are_usage_errors() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        assert_eq!(run(&args(&[])), EXIT_USAGE);
+        assert_eq!(run(&args(&["frobnicate"])), EXIT_USAGE);
+        assert_eq!(run(&args(&["solve", "--bogus"])), EXIT_USAGE);
+        assert_eq!(run(&args(&["solve", "-f", "xml"])), EXIT_USAGE);
+        assert_eq!(run(&args(&["count", "-l", "many"])), EXIT_USAGE);
+        assert!(Options::parse(&args(&["-e"])).is_err());
+
+        let options = Options::parse(&args(&["-l", "5", "-u", "a.txt", "-"])).unwrap();
+        assert_eq!(options.limit, 5);
+        assert!(options.unique);
+        assert_eq!(options.inputs, vec!["a.txt", "-"]);
+        assert_eq!(Options::parse(&[]).unwrap().inputs, vec!["-"]);
+    }
+}
@@ -0,0 +1,427 @@
+use super::sudoku_deduction::{
+    positions, AlmostLockedSet, Candidate, Deduction, Pattern, Technique, Unit,
+};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_subsets::subsets_within;
+use super::sudoku_units::{cell_bit, col_of, row_of, CellMask, PEER_MASKS, UNITS};
+use super::sudoku_values::SudokuValues;
+use std::collections::HashSet;
+
+//An almost locked set (ALS) is N cells of a unit that, between them, can only be N + 1 digits.
+//Take away any one of the digits and the rest are locked into the cells: each of them has to be
+//in one of the cells. A single cell with two possible values is the smallest one.
+//
+//A restricted common candidate (RCC) of two sets is a digit both can be, where every place for
+//it in one sees every place for it in the other. It can be in at most one of the two, so the
+//other set is locked.
+
+///An almost locked set, with the places for each digit in it, and the cells that see every one
+///of those places
+struct Als {
+    unit: usize,
+    cells: Vec<usize>,
+    mask: CellMask,
+    digits: SudokuValues,
+    places: [CellMask; 10],
+    seen: [CellMask; 10],
+}
+
+impl Als {
+    fn pattern(&self) -> AlmostLockedSet {
+        return AlmostLockedSet {
+            unit: Unit::from_index(self.unit),
+            cells: positions(&self.cells),
+            digits: self.digits,
+        };
+    }
+
+    ///The digits that are restricted common candidates of the two sets, which can't share a cell
+    fn restricted_commons(&self, other: &Als) -> SudokuValues {
+        let mut commons = SudokuValues::empty();
+        if self.mask & other.mask != 0 {
+            return commons;
+        }
+        for digit in self.digits.intersection(other.digits) {
+            if other.places[digit] & !self.seen[digit] == 0 {
+                commons.insert(digit);
+            }
+        }
+        return commons;
+    }
+}
+
+///Every almost locked set of a grid's candidates, and the restricted common candidates of each
+///pair of them. It is built once for each state of the grid and shared by the ALS techniques.
+pub(crate) struct AlsGraph {
+    sets: Vec<Als>,
+    ///For each set, every other set it has a restricted common candidate with, in order
+    links: Vec<Vec<(usize, SudokuValues)>>,
+    ///The cells that can still be each digit
+    places: [CellMask; 10],
+}
+
+impl SudokuGrid {
+    ///ALS-XZ: two almost locked sets with a restricted common candidate x. One of them doesn't
+    ///have x, so it is locked, which puts any other digit z they share in one of the two. No cell
+    ///that sees every place for z in both can be z.
+    pub(crate) fn find_als_xz(&self, graph: &AlsGraph) -> Option<Deduction> {
+        for (i, a) in graph.sets.iter().enumerate() {
+            for (j, commons) in graph.links[i].iter().filter(|(j, _)| *j > i) {
+                let b = &graph.sets[*j];
+                for x in commons.iter() {
+                    let shared = a
+                        .digits
+                        .intersection(b.digits)
+                        .difference(SudokuValues::from_digit(x));
+                    let eliminations = graph.eliminations(&[a, b], shared);
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::AlsXz,
+                            pattern: Pattern::AlmostLockedSets {
+                                stem: None,
+                                sets: vec![a.pattern(), b.pattern()],
+                                links: vec![x],
+                            },
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///ALS-XY-Wing: a pivot set C with a restricted common candidate x with a set A, and a
+    ///different one y with a set B. C can't be without both x and y, so A or B is locked, and
+    ///a digit z they share, other than x and y, is in one of them.
+    pub(crate) fn find_als_xy_wing(&self, graph: &AlsGraph) -> Option<Deduction> {
+        for (pivot, linked) in graph.sets.iter().zip(graph.links.iter()) {
+            for (i, (a, a_commons)) in linked.iter().enumerate() {
+                for (b, b_commons) in linked[i + 1..].iter() {
+                    let (a, b) = (&graph.sets[*a], &graph.sets[*b]);
+                    if a.mask & b.mask != 0 {
+                        continue;
+                    }
+                    for x in a_commons.iter() {
+                        for y in b_commons.iter().filter(|y| *y != x) {
+                            let mut links = SudokuValues::from_digit(x);
+                            links.insert(y);
+                            let shared = a.digits.intersection(b.digits).difference(links);
+                            let eliminations = graph.eliminations(&[a, b], shared);
+                            if !eliminations.is_empty() {
+                                return Some(Deduction {
+                                    technique: Technique::AlsXyWing,
+                                    pattern: Pattern::AlmostLockedSets {
+                                        stem: None,
+                                        sets: vec![a.pattern(), b.pattern(), pivot.pattern()],
+                                        links: vec![x, y],
+                                    },
+                                    eliminations,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Death Blossom: a stem cell, and for each of its possible values an almost locked set, a
+    ///petal, whose places for that value all see the stem. Whatever the stem turns out to be, that
+    ///petal is locked, so a digit z that every petal can be, and the stem can't, is in one of the
+    ///petals.
+    pub(crate) fn find_death_blossom(&self, graph: &AlsGraph) -> Option<Deduction> {
+        for stem in 0..81 {
+            let stem_digits = self.candidates_at(stem);
+            if !(2..=3).contains(&stem_digits.len()) {
+                continue;
+            }
+            let stem_digits = stem_digits.iter().collect::<Vec<usize>>();
+            //The sets that could be the petal for each of the stem's values
+            let petals = stem_digits
+                .iter()
+                .map(|d| {
+                    graph
+                        .sets
+                        .iter()
+                        .filter(|s| s.mask & cell_bit(stem) == 0 && s.digits.contains(*d))
+                        .filter(|s| s.seen[*d] & cell_bit(stem) != 0)
+                        .collect::<Vec<&Als>>()
+                })
+                .collect::<Vec<Vec<&Als>>>();
+
+            for z in (1..=9).filter(|z| !stem_digits.contains(z)) {
+                let targets = graph.places[z];
+                let mut picked = Vec::new();
+                if let Some(eliminations) = pick_petals(&petals, z, targets, &mut picked) {
+                    return Some(Deduction {
+                        technique: Technique::DeathBlossom,
+                        pattern: Pattern::AlmostLockedSets {
+                            stem: Some((row_of(stem), col_of(stem))),
+                            sets: picked.iter().map(|s| s.pattern()).collect(),
+                            links: stem_digits,
+                        },
+                        eliminations,
+                    });
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Every almost locked set, with the links between them
+    pub(crate) fn als_graph(&self) -> AlsGraph {
+        let sets = self.almost_locked_sets();
+        //Restricted common candidates work both ways, so each pair only needs checking once
+        let mut links = vec![Vec::new(); sets.len()];
+        for (i, a) in sets.iter().enumerate() {
+            for (j, b) in sets.iter().enumerate().skip(i + 1) {
+                let commons = a.restricted_commons(b);
+                if !commons.is_empty() {
+                    links[i].push((j, commons));
+                    links[j].push((i, commons));
+                }
+            }
+        }
+        let mut places = [0; 10];
+        for cell in 0..81 {
+            for digit in self.candidates_at(cell) {
+                places[digit] |= cell_bit(cell);
+            }
+        }
+        return AlsGraph {
+            sets,
+            links,
+            places,
+        };
+    }
+
+    ///Every almost locked set in every unit. A set that lies in two units, such as the cells of
+    ///a row within one subgrid, is only listed once.
+    fn almost_locked_sets(&self) -> Vec<Als> {
+        let mut sets = Vec::new();
+        let mut found = HashSet::new();
+        for (u, unit) in UNITS.iter().enumerate() {
+            let open = unit
+                .iter()
+                .cloned()
+                .filter(|i| !self.candidates_at(*i).is_empty())
+                .collect::<Vec<usize>>();
+            let candidates = open
+                .iter()
+                .map(|i| self.candidates_at(*i))
+                .collect::<Vec<SudokuValues>>();
+
+            for size in 1..open.len() {
+                for picked in subsets_within(&candidates, size, size + 1) {
+                    let cells = picked.iter().map(|p| open[*p]).collect::<Vec<usize>>();
+                    let mask = cells.iter().fold(0, |m, i| m | cell_bit(*i));
+                    if !found.insert(mask) {
+                        continue;
+                    }
+                    let mut set = Als {
+                        unit: u,
+                        cells,
+                        mask,
+                        digits: SudokuValues::empty(),
+                        places: [0; 10],
+                        seen: [CellMask::MAX; 10],
+                    };
+                    for i in set.cells.iter() {
+                        for digit in self.candidates_at(*i) {
+                            set.digits.insert(digit);
+                            set.places[digit] |= cell_bit(*i);
+                            set.seen[digit] &= PEER_MASKS[*i];
+                        }
+                    }
+                    if set.digits.len() == size + 1 {
+                        sets.push(set);
+                    }
+                }
+            }
+        }
+        return sets;
+    }
+}
+
+impl AlsGraph {
+    ///Every cell that can be one of the digits, and sees every place for it in all of the sets
+    fn eliminations(&self, sets: &[&Als], digits: SudokuValues) -> Vec<Candidate> {
+        let used = sets.iter().fold(0, |m, s| m | s.mask);
+        let mut eliminations = Vec::new();
+        for digit in digits {
+            let targets = sets
+                .iter()
+                .fold(self.places[digit] & !used, |m, s| m & s.seen[digit]);
+            eliminations.extend(candidates_in(targets, digit));
+        }
+        return eliminations;
+    }
+}
+
+///Picks a petal for each of the stem's values in turn, narrowing down the cells that could lose
+///z, and giving up on a pick as soon as there are none left
+fn pick_petals<'a>(
+    petals: &[Vec<&'a Als>],
+    z: usize,
+    targets: CellMask,
+    picked: &mut Vec<&'a Als>,
+) -> Option<Vec<Candidate>> {
+    if picked.len() == petals.len() {
+        let used = picked.iter().fold(0, |m, s| m | s.mask);
+        return Some(candidates_in(targets & !used, z));
+    }
+    for petal in petals[picked.len()].iter() {
+        if !petal.digits.contains(z) || picked.iter().any(|s| s.mask & petal.mask != 0) {
+            continue;
+        }
+        let targets = targets & petal.seen[z];
+        if targets == 0 {
+            continue;
+        }
+        picked.push(petal);
+        let found = pick_petals(petals, z, targets, picked);
+        if found.as_ref().is_some_and(|e| !e.is_empty()) {
+            return found;
+        }
+        picked.pop();
+    }
+    return None;
+}
+
+fn candidates_in(mask: CellMask, digit: usize) -> Vec<Candidate> {
+    return (0..81)
+        .filter(|i| mask & cell_bit(*i) != 0)
+        .map(|i| Candidate::at(i, digit))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(unit: Unit, cells: &[(usize, usize)], digits: &[usize]) -> AlmostLockedSet {
+        return AlmostLockedSet {
+            unit,
+            cells: cells.to_vec(),
+            digits: SudokuValues::from_digits(digits),
+        };
+    }
+
+    #[test]
+    fn als_xz() {
+        //Partway through solving
+        //...3.6..9.9.....6..4.59......3.4.5....6.17..427.......8...6..7.......3.21.....8..
+        let grid = SudokuGrid::from_candidates(
+            "
+            7 25  1258 3     28   6      124 458 9
+            3 9   1258 12478 278  124    12  6   158
+            6 4   128  5     9    12     7   128 3
+            9 1   3    6     4    28     5   28  7
+            5 8   6    29    1    7      29  3   4
+            2 7   4    89    35   35     6   189 18
+            8 235 259  124   6    123459 149 7   15
+            4 6   579  178   578  1589   3   159 2
+            1 235 2579 247   2357 23459  8   459 6
+            ",
+        );
+        assert_eq!(
+            grid.find_als_xz(&grid.als_graph()),
+            Some(Deduction {
+                technique: Technique::AlsXz,
+                pattern: Pattern::AlmostLockedSets {
+                    stem: None,
+                    sets: vec![
+                        set(Unit::Column(7), &[(2, 7), (3, 7)], &[1, 2, 8]),
+                        set(Unit::Subgrid(1), &[(0, 4), (2, 5)], &[1, 2, 8]),
+                    ],
+                    links: vec![1],
+                },
+                eliminations: vec![Candidate::new(0, 7, 8)],
+            })
+        );
+    }
+
+    #[test]
+    fn als_xy_wing() {
+        //Partway through solving
+        //.9..36...27.........58..1.4..4.6.59.....4...78.9.....15...........3...2.....2576.
+        let grid = SudokuGrid::from_candidates(
+            "
+            4   9    1  7    3   6   28  5 28
+            2   7    8  145  15  14  9   3 6
+            36  36   5  8    9   2   1   7 4
+            7   123  4  12   6   8   5   9 23
+            136 1356 26 1259 4   139 236 8 7
+            8   2356 9  25   57  37  26  4 1
+            5   248  27 6    78  479 34  1 389
+            69  468  67 3    178 179 48  2 5
+            19  148  3  49   2   5   7   6 89
+            ",
+        );
+        assert_eq!(
+            grid.find_als_xy_wing(&grid.als_graph()),
+            Some(Deduction {
+                technique: Technique::AlsXyWing,
+                pattern: Pattern::AlmostLockedSets {
+                    stem: None,
+                    sets: vec![
+                        set(
+                            Unit::Column(5),
+                            &[(1, 5), (4, 5), (6, 5), (7, 5)],
+                            &[1, 3, 4, 7, 9],
+                        ),
+                        set(Unit::Subgrid(6), &[(7, 0), (7, 2), (8, 0)], &[1, 6, 7, 9]),
+                        set(Unit::Row(4), &[(4, 0), (4, 2), (4, 6)], &[1, 2, 3, 6]),
+                    ],
+                    links: vec![3, 1],
+                },
+                eliminations: vec![Candidate::new(7, 4, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn death_blossom() {
+        //Partway through solving
+        //..1........5291...48.3....2..3..8.1....14.82.......9...3..6..9..5.43.......9...6.
+        let grid = SudokuGrid::from_candidates(
+            "
+            6 2 1 57  8  4 57 3  9
+            3 7 5 2   9  1 6  4  8
+            4 8 9 3   57 6 1  57 2
+            2 9 3 567 57 8 45 1  46
+            5 6 7 1   4  9 8  2  3
+            8 1 4 567 2  3 9  57 67
+            1 3 2 8   6  5 47 9  47
+            9 5 6 4   3  7 2  8  1
+            7 4 8 9   1  2 3  6  5
+            ",
+        );
+        assert_eq!(
+            grid.find_death_blossom(&grid.als_graph()),
+            Some(Deduction {
+                technique: Technique::DeathBlossom,
+                pattern: Pattern::AlmostLockedSets {
+                    stem: Some((3, 6)),
+                    sets: vec![
+                        set(Unit::Row(3), &[(3, 3), (3, 4), (3, 8)], &[4, 5, 6, 7]),
+                        set(Unit::Row(5), &[(5, 7)], &[5, 7]),
+                    ],
+                    links: vec![4, 5],
+                },
+                eliminations: vec![Candidate::new(5, 3, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn nothing_on_an_open_grid() {
+        let grid = SudokuGrid::new();
+        let graph = grid.als_graph();
+        assert_eq!(grid.find_als_xz(&graph), None);
+        assert_eq!(grid.find_als_xy_wing(&graph), None);
+        assert_eq!(grid.find_death_blossom(&graph), None);
+    }
+}
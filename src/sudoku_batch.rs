@@ -0,0 +1,285 @@
+use super::sudoku_error::SudokuError;
+use super::sudoku_format::GridFormat;
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_solver::SudokuSolver;
+use std::io::{self, BufRead, Write};
+
+///One puzzle read from a file holding many of them
+#[derive(Debug)]
+pub struct BatchPuzzle {
+    ///One based position of the puzzle in the input
+    pub number: usize,
+    ///Zero based line the puzzle starts on
+    pub line: usize,
+    pub format: GridFormat,
+    ///The parsed grid, or why it couldn't be parsed
+    pub grid: Result<SudokuGrid, SudokuError>,
+}
+
+///Streams puzzles out of a reader one at a time, so files with thousands of puzzles never have
+///to be held in memory.
+///
+///Puzzles are either single lines of 81 characters, or blocks of nine comma delimited lines
+///separated by blank lines. Both can be mixed in the same input. A puzzle that can't be parsed
+///is still returned, with the error in place of its grid, and reading carries on with the next
+///one.
+pub struct PuzzleReader<R: BufRead> {
+    lines: io::Lines<R>,
+    line_num: usize,
+    puzzle_num: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> PuzzleReader<R> {
+    pub fn new(reader: R) -> Self {
+        PuzzleReader {
+            lines: reader.lines(),
+            line_num: 0,
+            puzzle_num: 0,
+            finished: false,
+        }
+    }
+
+    fn next_line(&mut self) -> Option<io::Result<String>> {
+        let line = self.lines.next();
+        if line.is_some() {
+            self.line_num += 1;
+        }
+        return line;
+    }
+}
+
+impl<R: BufRead> Iterator for PuzzleReader<R> {
+    type Item = BatchPuzzle;
+
+    fn next(&mut self) -> Option<BatchPuzzle> {
+        if self.finished {
+            return None;
+        }
+
+        //Skip over the blank lines between puzzles
+        let first = loop {
+            match self.next_line() {
+                Some(Ok(l)) if l.trim().is_empty() => continue,
+                Some(Ok(l)) => break l,
+                Some(Err(e)) => {
+                    self.finished = true;
+                    self.puzzle_num += 1;
+                    return Some(BatchPuzzle {
+                        number: self.puzzle_num,
+                        line: self.line_num - 1,
+                        format: GridFormat::Line,
+                        grid: Err(SudokuError::Io(e)),
+                    });
+                }
+                None => return None,
+            }
+        };
+        self.puzzle_num += 1;
+        let start = self.line_num - 1;
+        let format = GridFormat::detect(&first);
+
+        let mut block = vec![first];
+        if format == GridFormat::Csv {
+            //A comma delimited puzzle carries on until the next blank line
+            loop {
+                match self.next_line() {
+                    Some(Ok(l)) if l.trim().is_empty() => break,
+                    Some(Ok(l)) => block.push(l),
+                    Some(Err(e)) => {
+                        self.finished = true;
+                        return Some(BatchPuzzle {
+                            number: self.puzzle_num,
+                            line: start,
+                            format,
+                            grid: Err(SudokuError::Io(e)),
+                        });
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        return Some(BatchPuzzle {
+            number: self.puzzle_num,
+            line: start,
+            format,
+            grid: SudokuGrid::parse_str_as(&block.join("\n"), format),
+        });
+    }
+}
+
+///How a batch run went
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub solved: usize,
+    pub unsolvable: usize,
+    pub invalid: usize,
+}
+
+impl BatchSummary {
+    pub fn total(&self) -> usize {
+        return self.solved + self.unsolvable + self.invalid;
+    }
+}
+
+///Solves every puzzle in `reader` with `solver`, writing one result per puzzle to `writer` in
+///the same order as the input. Solutions are written in `format`, or in each puzzle's own format if that is
+///`None`. A puzzle that can't be read or solved gets a line describing the error in place of its
+///solution, so one bad puzzle doesn't stop the rest of the run.
+///Only failing to write the output is treated as an error.
+pub fn solve_batch<R: BufRead, W: Write + ?Sized>(
+    reader: R,
+    writer: &mut W,
+    format: Option<GridFormat>,
+    solver: &dyn SudokuSolver,
+) -> io::Result<BatchSummary> {
+    let mut summary = BatchSummary::default();
+
+    for puzzle in PuzzleReader::new(reader) {
+        let out_format = format.unwrap_or(puzzle.format);
+        let result = match puzzle.grid {
+            Ok(grid) => solver.solve(&grid),
+            Err(e) => Err(e),
+        };
+
+        let output = match result {
+            Ok(solved) => {
+                summary.solved += 1;
+                solved.format_as(out_format)
+            }
+            Err(e) => {
+                if e.is_unsolvable() {
+                    summary.unsolvable += 1;
+                } else {
+                    summary.invalid += 1;
+                }
+                format!(
+                    "Puzzle {} (line {}): {}\n",
+                    puzzle.number,
+                    puzzle.line + 1,
+                    e
+                )
+            }
+        };
+
+        match out_format {
+            GridFormat::Line => writeln!(writer, "{}", output.trim_end())?,
+            //Keep comma delimited puzzles separated by a blank line, the same as the input
+            GridFormat::Csv => writeln!(writer, "{}", output)?,
+        }
+    }
+    writer.flush()?;
+    return Ok(summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_solver::BacktrackingSolver;
+
+    const LINE: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    const SOLVED: &str =
+        "561932874297458613843761259958174362726385491134629785485296137319847526672513948";
+    //r1c1 given as 2, which leaves the puzzle without a solution
+    const UNSOLVABLE: &str =
+        "261...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    const CSV: &str = "*,*,*,2,1,*,*,*,*
+*,*,7,3,*,*,*,*,*
+*,5,8,*,*,*,*,*,*
+4,3,*,*,*,*,*,*,*
+2,*,*,*,*,*,*,*,8
+*,*,*,*,*,*,*,7,6
+*,*,*,*,*,*,2,5,*
+*,*,*,*,*,7,3,*,*
+*,*,*,*,9,8,*,*,*";
+
+    ///A line puzzle, a comma delimited one, one that is too short and one with no solution,
+    ///with blank lines here and there
+    fn mixed() -> String {
+        return format!("{}\n\n{}\n\n\n{}\n{}\n", LINE, CSV, &LINE[..80], UNSOLVABLE);
+    }
+
+    #[test]
+    fn reads_every_puzzle_with_its_position() {
+        let input = mixed();
+        let puzzles = PuzzleReader::new(input.as_bytes()).collect::<Vec<BatchPuzzle>>();
+        let found = puzzles
+            .iter()
+            .map(|p| (p.number, p.line, p.format))
+            .collect::<Vec<(usize, usize, GridFormat)>>();
+        assert_eq!(
+            found,
+            vec![
+                (1, 0, GridFormat::Line),
+                (2, 2, GridFormat::Csv),
+                (3, 13, GridFormat::Line),
+                (4, 14, GridFormat::Line),
+            ]
+        );
+        assert_eq!(puzzles[0].grid.as_ref().unwrap().to_line(), LINE);
+        assert_eq!(puzzles[1].grid.as_ref().unwrap().get_value(0, 3), Some(2));
+        assert!(matches!(
+            puzzles[2].grid,
+            Err(SudokuError::WrongLength { found: 80 })
+        ));
+        assert!(puzzles[3].grid.is_ok());
+    }
+
+    #[test]
+    fn solves_every_puzzle_in_order() {
+        let input = mixed();
+        let mut output = Vec::new();
+        let summary = solve_batch(
+            input.as_bytes(),
+            &mut output,
+            Some(GridFormat::Line),
+            &BacktrackingSolver::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            summary,
+            BatchSummary {
+                solved: 2,
+                unsolvable: 1,
+                invalid: 1
+            }
+        );
+        assert_eq!(summary.total(), 4);
+
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], SOLVED);
+        assert_eq!(lines[1].len(), 81);
+        assert_eq!(
+            lines[2],
+            "Puzzle 3 (line 14): A single line puzzle must have 81 characters, found 80"
+        );
+        assert_eq!(
+            lines[3],
+            "Puzzle 4 (line 15): Row 2 Column 1 has no possible values, the puzzle has no solution"
+        );
+    }
+
+    #[test]
+    fn keeps_each_puzzle_format() {
+        let input = format!("{}\n\n{}\n", CSV, LINE);
+        let mut output = Vec::new();
+        solve_batch(
+            input.as_bytes(),
+            &mut output,
+            None,
+            &BacktrackingSolver::new(),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<&str>>();
+        //Nine rows and a blank line, then the single line
+        assert_eq!(lines.len(), 11);
+        assert!(lines[..9].iter().all(|l| l.split(',').count() == 9));
+        assert_eq!(lines[9], "");
+        assert_eq!(lines[10], SOLVED);
+    }
+}
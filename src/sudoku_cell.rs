@@ -3,23 +3,49 @@ use std::fmt;
 
 #[derive(Clone)]
 pub struct SudokuCell {
-    pub cur_val: Option<usize>,
-    pub row: usize,
-    pub col: usize,
-    pub subgrid: usize,
-    pub possible_vals: SudokuValues,
+    pub(crate) cur_val: Option<usize>,
+    pub(crate) row: usize,
+    pub(crate) col: usize,
+    pub(crate) subgrid: usize,
+    pub(crate) possible_vals: SudokuValues,
 }
 
 impl SudokuCell {
-    pub fn new(r: usize, c: usize, s: usize) -> Self {
+    pub(crate) fn new(r: usize, c: usize, s: usize) -> Self {
         SudokuCell {
             cur_val: None,
             row: r,
             col: c,
             subgrid: s,
-            possible_vals: SudokuValues { values: [true; 9] },
+            possible_vals: SudokuValues::all(),
         }
     }
+
+    ///The cell's value, if it has been given or solved
+    pub fn value(&self) -> Option<usize> {
+        return self.cur_val;
+    }
+
+    ///Zero based row index
+    pub fn row(&self) -> usize {
+        return self.row;
+    }
+
+    ///Zero based column index
+    pub fn col(&self) -> usize {
+        return self.col;
+    }
+
+    ///Zero based subgrid index, counted left to right, top to bottom
+    pub fn subgrid(&self) -> usize {
+        return self.subgrid;
+    }
+
+    ///The values that are still possible for this cell.
+    ///Only meaningful while the cell has no value.
+    pub fn candidates(&self) -> &SudokuValues {
+        return &self.possible_vals;
+    }
 }
 
 impl fmt::Debug for SudokuCell {
@@ -42,6 +68,6 @@ impl fmt::Debug for SudokuCell {
                 self.subgrid + 1,
                 self.possible_vals
             )
-        }
+        };
     }
 }
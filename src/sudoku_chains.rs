@@ -0,0 +1,597 @@
+use super::sudoku_deduction::{positions, Candidate, ChainNode, Deduction, Pattern, Technique};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_units::{
+    cell_bit, col_of, row_of, sees, subgrid_of, CellMask, PEER_MASKS, SUBGRID_UNIT, UNITS,
+};
+use std::collections::HashMap;
+
+//Chains are built on a graph of candidates. Each node is a digit in one cell, or for grouped
+//nodes a digit in two or three cells where a row or column meets a subgrid, taken to mean "the
+//digit is in one of these cells". A strong link between two nodes means at least one of them is
+//true, a weak link means at most one of them is.
+//
+//An alternating inference chain (AIC) starts and ends with a strong link, and alternates strong
+//and weak links in between. If the first node is false the next one is true, so the one after
+//that is false, and so on down the chain, leaving the last node true. So either the first node
+//or the last one is true, and anything that can't be true alongside either of them is ruled out.
+
+///The longest chain looked for, in links. Longer chains are rare, and slow to search for.
+const MAX_CHAIN_LINKS: usize = 13;
+
+///Which links a chain may use
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ChainKind {
+    ///Strong and weak links on a single digit between single cells
+    X,
+    ///Strong links inside cells with two possible values, weak links on a digit between cells
+    XY,
+    ///Any link, including grouped nodes
+    Alternating,
+}
+
+///A node of the candidate graph: the digit is in one of the cells
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Node {
+    digit: usize,
+    cells: Vec<usize>,
+}
+
+///Every node and link of a grid's candidates. It is built once for each state of the grid and
+///shared by every kind of chain, which only differ in the links they may follow.
+pub(crate) struct ChainGraph {
+    nodes: Vec<Node>,
+    strong: Vec<Vec<usize>>,
+    weak: Vec<Vec<usize>>,
+    ///The cells, outside each node, that see all of its cells
+    seen: Vec<CellMask>,
+    ///The cells that can still be each digit
+    places: [CellMask; 10],
+}
+
+impl SudokuGrid {
+    ///An X-Chain is an AIC on a single digit, where every node is one cell
+    pub(crate) fn find_x_chain(&self, graph: &ChainGraph) -> Option<Deduction> {
+        return self.find_chain(graph, ChainKind::X, Technique::XChain);
+    }
+
+    ///An XY-Chain is an AIC through cells that each have two possible values. Every strong link
+    ///is inside one of those cells, and every weak link joins two cells on the same digit. It
+    ///starts and ends on the same digit, which can't go anywhere that sees both ends.
+    pub(crate) fn find_xy_chain(&self, graph: &ChainGraph) -> Option<Deduction> {
+        return self.find_chain(graph, ChainKind::XY, Technique::XYChain);
+    }
+
+    ///Any alternating inference chain, including grouped nodes
+    pub(crate) fn find_alternating_chain(&self, graph: &ChainGraph) -> Option<Deduction> {
+        return self.find_chain(
+            graph,
+            ChainKind::Alternating,
+            Technique::AlternatingInferenceChain,
+        );
+    }
+
+    ///Searches outward from every node, shortest chains first, for a chain whose ends rule
+    ///something out
+    fn find_chain(
+        &self,
+        graph: &ChainGraph,
+        kind: ChainKind,
+        technique: Technique,
+    ) -> Option<Deduction> {
+        //The states are (node, whether the chain reached it by a strong link), stored at
+        //node * 2 + 1 when it was. Each records the state the chain came from, and the start
+        //it was reached from, so nothing needs clearing between starts.
+        let states = graph.nodes.len() * 2;
+        let mut parent = vec![(0, false); states];
+        let mut reached_from = vec![usize::MAX; states];
+
+        for start in 0..graph.nodes.len() {
+            if !graph.strong[start]
+                .iter()
+                .any(|n| graph.allows(kind, start, *n, true))
+            {
+                continue;
+            }
+            //A chain ending on the start's digit can only rule it out where the start sees, and
+            //only a chain from a single cell can end on another digit
+            let first = &graph.nodes[start];
+            let same_digit_only = kind != ChainKind::Alternating || first.cells.len() > 1;
+            if same_digit_only && graph.places[first.digit] & graph.seen[start] == 0 {
+                continue;
+            }
+            let mut frontier = vec![(start, false)];
+            let mut length = 0;
+
+            while !frontier.is_empty() && length < MAX_CHAIN_LINKS {
+                length += 1;
+                let mut next = Vec::new();
+                for (node, by_strong) in frontier {
+                    //Links have to alternate, starting with a strong one
+                    let links = if by_strong {
+                        &graph.weak[node]
+                    } else {
+                        &graph.strong[node]
+                    };
+                    for other in links.iter() {
+                        let state = (*other, !by_strong);
+                        let slot = state_slot(state);
+                        if *other == start
+                            || reached_from[slot] == start
+                            || !graph.allows(kind, node, *other, !by_strong)
+                        {
+                            continue;
+                        }
+                        reached_from[slot] = start;
+                        parent[slot] = (node, by_strong);
+                        next.push(state);
+
+                        //A chain needs at least two strong links to say anything new
+                        if !state.1 || length < 3 {
+                            continue;
+                        }
+                        let last = &graph.nodes[*other];
+                        if kind != ChainKind::Alternating && first.digit != last.digit {
+                            continue;
+                        }
+                        let eliminations = self.chain_eliminations(graph, start, *other);
+                        if eliminations.is_empty() {
+                            continue;
+                        }
+                        let chain = chain_path(&parent, start, state);
+                        if graph.is_valid(&chain) {
+                            return Some(Deduction {
+                                technique,
+                                pattern: Pattern::Chain {
+                                    nodes: chain
+                                        .iter()
+                                        .map(|n| ChainNode {
+                                            digit: graph.nodes[*n].digit,
+                                            cells: positions(&graph.nodes[*n].cells),
+                                        })
+                                        .collect(),
+                                },
+                                eliminations,
+                            });
+                        }
+                    }
+                }
+                frontier = next;
+            }
+        }
+        return None;
+    }
+
+    ///What can't be true when at least one of the two nodes is
+    fn chain_eliminations(&self, graph: &ChainGraph, a: usize, b: usize) -> Vec<Candidate> {
+        let (first, last) = (&graph.nodes[a], &graph.nodes[b]);
+        if first.digit == last.digit {
+            let targets = graph.places[first.digit] & graph.seen[a] & graph.seen[b];
+            if targets == 0 {
+                return Vec::new();
+            }
+            return (0..81)
+                .filter(|i| targets & cell_bit(*i) != 0)
+                .map(|i| Candidate::at(i, first.digit))
+                .collect();
+        }
+
+        let mut eliminations = Vec::new();
+        if first.cells.len() != 1 || last.cells.len() != 1 {
+            return eliminations;
+        }
+        let (first_cell, last_cell) = (first.cells[0], last.cells[0]);
+        if first_cell == last_cell {
+            //The cell has to be one of the two digits
+            for value in self.candidates_at(first_cell) {
+                if value != first.digit && value != last.digit {
+                    eliminations.push(Candidate::at(first_cell, value));
+                }
+            }
+        } else if sees(first_cell, last_cell) {
+            //If the first cell were the last digit, the last cell couldn't be, so the first cell
+            //would have to be the first digit
+            if self.candidates_at(first_cell).contains(last.digit) {
+                eliminations.push(Candidate::at(first_cell, last.digit));
+            }
+            if self.candidates_at(last_cell).contains(first.digit) {
+                eliminations.push(Candidate::at(last_cell, first.digit));
+            }
+        }
+        return eliminations;
+    }
+
+    ///The graph of every candidate, every grouped node, and every link between them
+    pub(crate) fn chain_graph(&self) -> ChainGraph {
+        let mut graph = ChainGraph {
+            nodes: Vec::new(),
+            strong: Vec::new(),
+            weak: Vec::new(),
+            seen: Vec::new(),
+            places: [0; 10],
+        };
+        let mut ids: HashMap<Node, usize> = HashMap::new();
+
+        for cell in 0..81 {
+            for digit in self.candidates_at(cell) {
+                graph.add_node(&mut ids, digit, vec![cell]);
+                graph.places[digit] |= cell_bit(cell);
+            }
+        }
+        //Where each row and column meets each subgrid
+        for line in 0..SUBGRID_UNIT {
+            for subgrid in UNITS[SUBGRID_UNIT..].iter() {
+                for digit in 1..=9 {
+                    let cells = self
+                        .places_in(line, digit)
+                        .into_iter()
+                        .filter(|i| subgrid.contains(i))
+                        .collect::<Vec<usize>>();
+                    if cells.len() >= 2 {
+                        graph.add_node(&mut ids, digit, cells);
+                    }
+                }
+            }
+        }
+
+        //Strong links on a digit within a unit
+        for unit in 0..UNITS.len() {
+            for digit in 1..=9 {
+                let places = self.places_in(unit, digit);
+                if places.len() == 2 {
+                    graph.link_strong(&ids, digit, &[places[0]], &[places[1]]);
+                } else if places.len() > 2 {
+                    for (first, second) in split_places(unit, &places) {
+                        graph.link_strong(&ids, digit, &first, &second);
+                    }
+                }
+            }
+        }
+        //Strong links inside cells with two possible values, weak links inside every cell
+        for cell in 0..81 {
+            let digits = self.candidates_at(cell).iter().collect::<Vec<usize>>();
+            for (i, first) in digits.iter().enumerate() {
+                for second in digits[i + 1..].iter() {
+                    let a = ids[&Node {
+                        digit: *first,
+                        cells: vec![cell],
+                    }];
+                    let b = ids[&Node {
+                        digit: *second,
+                        cells: vec![cell],
+                    }];
+                    if digits.len() == 2 {
+                        graph.link(a, b, true);
+                    }
+                    graph.link(a, b, false);
+                }
+            }
+        }
+
+        //Weak links on a digit between nodes that see each other
+        let mut by_digit = vec![Vec::new(); 10];
+        for (n, node) in graph.nodes.iter().enumerate() {
+            by_digit[node.digit].push(n);
+        }
+        for nodes in by_digit.iter() {
+            for (i, a) in nodes.iter().enumerate() {
+                for b in nodes[i + 1..].iter() {
+                    let seen = graph.seen[*a];
+                    if graph.nodes[*b]
+                        .cells
+                        .iter()
+                        .all(|i| seen & cell_bit(*i) != 0)
+                    {
+                        graph.link(*a, *b, false);
+                    }
+                }
+            }
+        }
+        return graph;
+    }
+}
+
+impl ChainGraph {
+    ///Whether a chain of the given kind may follow the link between the two nodes
+    fn allows(&self, kind: ChainKind, a: usize, b: usize, strong: bool) -> bool {
+        let (first, second) = (&self.nodes[a], &self.nodes[b]);
+        let single = first.cells.len() == 1 && second.cells.len() == 1;
+        return match kind {
+            ChainKind::X => single && first.digit == second.digit,
+            ChainKind::XY => single && (first.digit != second.digit) == strong,
+            ChainKind::Alternating => true,
+        };
+    }
+
+    fn add_node(&mut self, ids: &mut HashMap<Node, usize>, digit: usize, cells: Vec<usize>) {
+        let seen = cells.iter().fold(CellMask::MAX, |m, i| m & PEER_MASKS[*i]);
+        let node = Node { digit, cells };
+        ids.insert(node.clone(), self.nodes.len());
+        self.seen.push(seen);
+        self.nodes.push(node);
+        self.strong.push(Vec::new());
+        self.weak.push(Vec::new());
+    }
+
+    fn link_strong(
+        &mut self,
+        ids: &HashMap<Node, usize>,
+        digit: usize,
+        first: &[usize],
+        second: &[usize],
+    ) {
+        let a = ids.get(&Node {
+            digit,
+            cells: first.to_vec(),
+        });
+        let b = ids.get(&Node {
+            digit,
+            cells: second.to_vec(),
+        });
+        if let (Some(a), Some(b)) = (a, b) {
+            self.link(*a, *b, true);
+        }
+    }
+
+    fn link(&mut self, a: usize, b: usize, strong: bool) {
+        let links = if strong {
+            &mut self.strong
+        } else {
+            &mut self.weak
+        };
+        if !links[a].contains(&b) {
+            links[a].push(b);
+            links[b].push(a);
+        }
+    }
+
+    ///A chain can't use a cell twice for the same digit
+    fn is_valid(&self, chain: &[usize]) -> bool {
+        for (i, a) in chain.iter().enumerate() {
+            for b in chain[i + 1..].iter() {
+                let (first, second) = (&self.nodes[*a], &self.nodes[*b]);
+                if first.digit == second.digit
+                    && first.cells.iter().any(|c| second.cells.contains(c))
+                {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+}
+
+///The nodes of the chain from the start to the given state, following the parents back
+fn chain_path(parent: &[(usize, bool)], start: usize, end: (usize, bool)) -> Vec<usize> {
+    let mut chain = vec![end.0];
+    let mut state = end;
+    while state != (start, false) {
+        state = parent[state_slot(state)];
+        chain.push(state.0);
+    }
+    chain.reverse();
+    return chain;
+}
+
+fn state_slot((node, by_strong): (usize, bool)) -> usize {
+    return node * 2 + by_strong as usize;
+}
+
+///Splits the places for a digit in a unit into two groups that each fit in a grouped node, in
+///every way that can be done. Either group has to hold the digit, so they are strongly linked.
+fn split_places(unit: usize, places: &[usize]) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let mut splits = Vec::new();
+    //A row or column is split between subgrids, a subgrid between rows or between columns
+    let keys: Vec<fn(usize) -> usize> = if unit < SUBGRID_UNIT {
+        vec![subgrid_of]
+    } else {
+        vec![row_of, col_of]
+    };
+    for key in keys {
+        let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+        for i in places.iter() {
+            match groups.iter_mut().find(|(k, _)| *k == key(*i)) {
+                Some((_, cells)) => cells.push(*i),
+                None => groups.push((key(*i), vec![*i])),
+            }
+        }
+        if groups.len() == 2 {
+            splits.push((groups[0].1.clone(), groups[1].1.clone()));
+        }
+    }
+    return splits;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(digit: usize, cells: &[(usize, usize)]) -> ChainNode {
+        return ChainNode {
+            digit,
+            cells: cells.to_vec(),
+        };
+    }
+
+    #[test]
+    fn x_chain() {
+        //Partway through solving
+        //.3........2.1...69..67..1..7....23....4.958....8.........94372....2........68..1.
+        let grid = SudokuGrid::from_candidates(
+            "
+            1   3   9 5 6 4 2  8 7
+            45  2   7 1 3 8 45 6 9
+            458 458 6 7 2 9 1  3 45
+            7   9   5 8 1 2 3  4 6
+            2   6   4 3 9 5 8  7 1
+            3   1   8 4 7 6 9  5 2
+            6   58  1 9 4 3 7  2 58
+            48  7   3 2 5 1 6  9 48
+            9   45  2 6 8 7 45 1 3
+            ",
+        );
+        assert_eq!(
+            grid.find_x_chain(&grid.chain_graph()),
+            Some(Deduction {
+                technique: Technique::XChain,
+                pattern: Pattern::Chain {
+                    nodes: vec![
+                        node(4, &[(1, 0)]),
+                        node(4, &[(1, 6)]),
+                        node(4, &[(2, 8)]),
+                        node(4, &[(7, 8)]),
+                    ],
+                },
+                eliminations: vec![Candidate::new(7, 0, 4)],
+            })
+        );
+    }
+
+    #[test]
+    fn xy_chain() {
+        //Partway through solving
+        //........2.2....48..16.2..7..8..7....3....5...6.5.....9..84...65...9......3.7.2.9.
+        let grid = SudokuGrid::from_candidates(
+            "
+            8 5  7 3  4 6  9   1   2
+            9 2  3 5  1 7  4   8   6
+            4 1  6 8  2 9  5   7   3
+            2 8  9 16 7 34 36  5   14
+            3 47 1 26 9 5  678 24  478
+            6 47 5 12 8 34 137 234 9
+            7 9  8 4  3 1  2   6   5
+            1 6  2 9  5 8  37  34  47
+            5 3  4 7  6 2  18  9   18
+            ",
+        );
+        assert_eq!(
+            grid.find_xy_chain(&grid.chain_graph()),
+            Some(Deduction {
+                technique: Technique::XYChain,
+                pattern: Pattern::Chain {
+                    nodes: vec![
+                        node(3, &[(3, 6)]),
+                        node(6, &[(3, 6)]),
+                        node(6, &[(3, 3)]),
+                        node(1, &[(3, 3)]),
+                        node(1, &[(3, 8)]),
+                        node(4, &[(3, 8)]),
+                        node(4, &[(7, 8)]),
+                        node(7, &[(7, 8)]),
+                        node(7, &[(7, 6)]),
+                        node(3, &[(7, 6)]),
+                    ],
+                },
+                eliminations: vec![Candidate::new(5, 6, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn alternating_chain() {
+        //Partway through solving
+        //..3.1.....5....783..7..9......8.2......46..3.4.....5..16.9..........8.1.....21.57
+        let grid = SudokuGrid::from_candidates(
+            "
+            68 48 3   7 1 5 2   469 469
+            9  5  1   2 4 6 7   8   3
+            26 24 7   3 8 9 1   46  5
+            37 37 69  8 5 2 469 469 1
+            5  1  29  4 6 7 8   3   29
+            4  28 268 1 9 3 5   7   26
+            1  6  5   9 7 4 3   2   8
+            27 79 24  5 3 8 469 1   469
+            38 39 48  6 2 1 49  5   7
+            ",
+        );
+        assert_eq!(
+            grid.find_alternating_chain(&grid.chain_graph()),
+            Some(Deduction {
+                technique: Technique::AlternatingInferenceChain,
+                pattern: Pattern::Chain {
+                    nodes: vec![
+                        node(6, &[(0, 0)]),
+                        node(6, &[(2, 0)]),
+                        node(2, &[(2, 0)]),
+                        node(2, &[(7, 0)]),
+                        node(2, &[(7, 2)]),
+                        node(4, &[(7, 2)]),
+                        node(4, &[(7, 8)]),
+                        node(4, &[(0, 8)]),
+                    ],
+                },
+                eliminations: vec![Candidate::new(0, 8, 6)],
+            })
+        );
+    }
+
+    #[test]
+    fn alternating_chain_with_a_grouped_node() {
+        //Partway through solving
+        //.....3...7......5.....65..8......43......95.6...73......94..61...1.....22.63.8..4
+        let grid = SudokuGrid::from_candidates(
+            "
+            5   28  4  18 7  3  12 6 9
+            7   6   28 9  48 14 12 5 3
+            19  19  3  2  6  5  7  4 8
+            189 289 28 6  5  12 4  3 7
+            13  134 7  18 48 9  5  2 6
+            6   24  5  7  3  24 8  9 1
+            38  38  9  4  2  7  6  1 5
+            4   7   1  5  9  6  3  8 2
+            2   5   6  3  1  8  9  7 4
+            ",
+        );
+        assert_eq!(
+            grid.find_alternating_chain(&grid.chain_graph()),
+            Some(Deduction {
+                technique: Technique::AlternatingInferenceChain,
+                pattern: Pattern::Chain {
+                    nodes: vec![
+                        node(2, &[(0, 1)]),
+                        node(2, &[(0, 6)]),
+                        node(1, &[(0, 6)]),
+                        node(1, &[(0, 3)]),
+                        node(1, &[(1, 5)]),
+                        node(1, &[(3, 5)]),
+                        node(2, &[(3, 5)]),
+                        node(2, &[(3, 1), (3, 2)]),
+                    ],
+                },
+                eliminations: vec![Candidate::new(5, 1, 2)],
+            })
+        );
+    }
+
+    #[test]
+    fn chains_in_eureka_notation() {
+        let chain = Pattern::Chain {
+            nodes: vec![
+                node(2, &[(0, 1)]),
+                node(2, &[(0, 6)]),
+                node(1, &[(0, 6)]),
+                node(1, &[(3, 5)]),
+                node(2, &[(3, 5)]),
+                node(2, &[(3, 1), (3, 2)]),
+            ],
+        };
+        assert_eq!(
+            chain.eureka().unwrap(),
+            "(2)r1c2=(2)r1c7-(1)r1c7=(1)r4c6-(2)r4c6=(2)r4c23"
+        );
+        assert_eq!(chain.to_string(), chain.eureka().unwrap());
+        let bug = Pattern::Bug {
+            cell: (0, 0),
+            digit: 1,
+        };
+        assert_eq!(bug.eureka(), None);
+    }
+
+    #[test]
+    fn nothing_without_strong_links() {
+        let grid = SudokuGrid::new();
+        let graph = grid.chain_graph();
+        assert_eq!(grid.find_x_chain(&graph), None);
+        assert_eq!(grid.find_xy_chain(&graph), None);
+        assert_eq!(grid.find_alternating_chain(&graph), None);
+    }
+}
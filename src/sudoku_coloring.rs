@@ -0,0 +1,306 @@
+use super::sudoku_deduction::{positions, Candidate, Deduction, Pattern, StrongLink, Technique};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_units::sees;
+
+//Coloring works on the conjugate pair graph of a digit: the cells it can go in, joined wherever
+//two of them are the only places left for it in some unit. Exactly one end of each pair holds
+//the digit, so giving the ends of each pair opposite colors splits every connected cluster into
+//two colors, one of which holds the digit in all of its cells and the other in none.
+
+///A connected cluster of the conjugate pair graph, as the cells of each color
+type Cluster = [Vec<usize>; 2];
+
+impl SudokuGrid {
+    ///The conjugate pairs of the digit: every row, column and subgrid where it has exactly two
+    ///places left
+    pub fn conjugate_pairs(&self, digit: usize) -> Vec<StrongLink> {
+        return self
+            .strong_links(digit)
+            .iter()
+            .map(|(unit, a, b)| StrongLink::between(*unit, &[*a], &[*b]))
+            .collect();
+    }
+
+    ///Color wrap: two cells of the same color see each other, so that color can't be the one
+    ///holding the digit. None of its cells can be the digit.
+    pub(crate) fn find_color_wrap(&self) -> Option<Deduction> {
+        for digit in 1..=9 {
+            for cluster in self.color_clusters(digit) {
+                for color in 0..2 {
+                    let cells = &cluster[color];
+                    let clash = cells.iter().any(|a| cells.iter().any(|b| sees(*a, *b)));
+                    if clash {
+                        let eliminations = cells.iter().map(|i| Candidate::at(*i, digit)).collect();
+                        return Some(coloring(
+                            Technique::ColorWrap,
+                            digit,
+                            &[cluster],
+                            eliminations,
+                        ));
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Color trap: a cell outside the cluster that sees both colors can't be the digit, since
+    ///one of the colors has to hold it
+    pub(crate) fn find_color_trap(&self) -> Option<Deduction> {
+        for digit in 1..=9 {
+            for cluster in self.color_clusters(digit) {
+                let eliminations = self.seen_by_colors(digit, &cluster[0], &cluster[1]);
+                if !eliminations.is_empty() {
+                    return Some(coloring(
+                        Technique::ColorTrap,
+                        digit,
+                        &[cluster],
+                        eliminations,
+                    ));
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Multi-coloring compares two clusters. If a color of the first sees a color of the second,
+    ///they can't both hold the digit, so one of the two opposite colors has to. Cells that see
+    ///both of those opposite colors can't be the digit. And if a color sees both colors of the
+    ///other cluster, it can't hold the digit at all.
+    pub(crate) fn find_multi_coloring(&self) -> Option<Deduction> {
+        for digit in 1..=9 {
+            let clusters = self.color_clusters(digit);
+            for (i, first) in clusters.iter().enumerate() {
+                for (j, second) in clusters.iter().enumerate() {
+                    if i == j {
+                        continue;
+                    }
+                    for a in 0..2 {
+                        let sees_b = [0, 1].map(|b| colors_see(&first[a], &second[b]));
+
+                        let eliminations = if sees_b[0] && sees_b[1] {
+                            first[a].iter().map(|c| Candidate::at(*c, digit)).collect()
+                        } else if let Some(b) = (0..2).find(|b| sees_b[*b]) {
+                            self.seen_by_colors(digit, &first[1 - a], &second[1 - b])
+                        } else {
+                            Vec::new()
+                        };
+                        if !eliminations.is_empty() {
+                            let clusters = [first.clone(), second.clone()];
+                            return Some(coloring(
+                                Technique::MultiColoring,
+                                digit,
+                                &clusters,
+                                eliminations,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Splits the conjugate pair graph of the digit into its connected clusters, and colors them
+    fn color_clusters(&self, digit: usize) -> Vec<Cluster> {
+        let links = self.strong_links(digit);
+        let mut colors: [Option<usize>; 81] = [None; 81];
+        let mut clusters = Vec::new();
+
+        for (_, start, _) in links.iter() {
+            if colors[*start].is_some() {
+                continue;
+            }
+            let mut cluster: Cluster = [Vec::new(), Vec::new()];
+            colors[*start] = Some(0);
+            let mut pending = vec![*start];
+            while let Some(cell) = pending.pop() {
+                let color = colors[cell].unwrap_or(0);
+                cluster[color].push(cell);
+                for (_, a, b) in links.iter() {
+                    let other = if *a == cell {
+                        *b
+                    } else if *b == cell {
+                        *a
+                    } else {
+                        continue;
+                    };
+                    if colors[other].is_none() {
+                        colors[other] = Some(1 - color);
+                        pending.push(other);
+                    }
+                }
+            }
+            cluster[0].sort_unstable();
+            cluster[1].sort_unstable();
+            clusters.push(cluster);
+        }
+        return clusters;
+    }
+
+    ///Cells outside both colors that could be the digit, and see a cell of each color
+    fn seen_by_colors(&self, digit: usize, first: &[usize], second: &[usize]) -> Vec<Candidate> {
+        return (0..81)
+            .filter(|i| !first.contains(i) && !second.contains(i))
+            .filter(|i| self.candidates_at(*i).contains(digit))
+            .filter(|i| first.iter().any(|c| sees(*i, *c)) && second.iter().any(|c| sees(*i, *c)))
+            .map(|i| Candidate::at(i, digit))
+            .collect();
+    }
+}
+
+///Returns true if a cell of the first color sees a cell of the second
+fn colors_see(first: &[usize], second: &[usize]) -> bool {
+    return first.iter().any(|a| second.iter().any(|b| sees(*a, *b)));
+}
+
+fn coloring(
+    technique: Technique,
+    digit: usize,
+    clusters: &[Cluster],
+    eliminations: Vec<Candidate>,
+) -> Deduction {
+    return Deduction {
+        technique,
+        pattern: Pattern::Coloring {
+            digit,
+            clusters: clusters
+                .iter()
+                .map(|c| [positions(&c[0]), positions(&c[1])])
+                .collect(),
+        },
+        eliminations,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_wrap() {
+        //Partway through solving
+        //........74.....12.8.352..4..3.......2.584.......1.5....7....3......6...898...175.
+        let grid = SudokuGrid::from_candidates(
+            "
+            5  2  69  69  1  4  8  3   7
+            4  69 7   3   8  69 1  2   5
+            8  1  3   5   2  7  69 4   69
+            17 3  19  26  79 26 5  8   4
+            2  69 5   8   4  3  69 7   1
+            67 4  8   1   79 5  2  69  3
+            16 7  14  249 5  8  3  169 29
+            3  5  12  7   6  29 4  19  8
+            9  8  246 24  3  1  7  5   26
+            ",
+        );
+        assert_eq!(
+            grid.find_color_wrap(),
+            Some(Deduction {
+                technique: Technique::ColorWrap,
+                pattern: Pattern::Coloring {
+                    digit: 9,
+                    clusters: vec![[
+                        vec![
+                            (0, 2),
+                            (1, 5),
+                            (2, 6),
+                            (3, 4),
+                            (4, 1),
+                            (5, 7),
+                            (6, 3),
+                            (6, 8),
+                            (7, 7),
+                        ],
+                        vec![(0, 3), (1, 1), (2, 8), (3, 2), (4, 6), (5, 4), (7, 5)],
+                    ]],
+                },
+                eliminations: vec![
+                    Candidate::new(0, 2, 9),
+                    Candidate::new(1, 5, 9),
+                    Candidate::new(2, 6, 9),
+                    Candidate::new(3, 4, 9),
+                    Candidate::new(4, 1, 9),
+                    Candidate::new(5, 7, 9),
+                    Candidate::new(6, 3, 9),
+                    Candidate::new(6, 8, 9),
+                    Candidate::new(7, 7, 9),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn color_trap() {
+        //Partway through solving
+        //.8.61...91....72....5....7...3...1.6....42......36.8.5.2.4...587....5.........9..
+        let grid = SudokuGrid::from_candidates(
+            "
+            2   8  7  6   1   4   5 3  9
+            1   36 69 59  35  7   2 8  4
+            4   39 5  289 238 38  6 7  1
+            58  4  3  78  578 9   1 2  6
+            56  1  68 58  4   2   3 9  7
+            9   7  2  3   6   1   8 4  5
+            36  2  1  4   9   36  7 5  8
+            7   69 89 128 238 5   4 16 23
+            368 5  4  127 237 368 9 16 23
+            ",
+        );
+        assert_eq!(
+            grid.find_color_trap(),
+            Some(Deduction {
+                technique: Technique::ColorTrap,
+                pattern: Pattern::Coloring {
+                    digit: 8,
+                    clusters: vec![[
+                        vec![(2, 5), (4, 2), (8, 0)],
+                        vec![(3, 0), (4, 3), (7, 2), (8, 5)],
+                    ]],
+                },
+                eliminations: vec![Candidate::new(2, 3, 8)],
+            })
+        );
+    }
+
+    #[test]
+    fn multi_coloring() {
+        //Partway through solving
+        //94.1...7.5..8...9...1.......3........64....23..9.1.6......5..424....6..5.7...21..
+        let grid = SudokuGrid::from_candidates(
+            "
+            9   4 36  1   236  5    23  7 8
+            5   2 367 8   46   347  34  9 1
+            37  8 1   249 2349 3479 234 5 6
+            278 3 78  6   249  49   5   1 47
+            1   6 4   5   7    8    9   2 3
+            27  5 9   234 1    34   6   8 47
+            6   9 38  37  5    1    78  4 2
+            4   1 2   79  89   6    78  3 5
+            38  7 5   34  348  2    1   6 9
+            ",
+        );
+        assert_eq!(
+            grid.find_multi_coloring(),
+            Some(Deduction {
+                technique: Technique::MultiColoring,
+                pattern: Pattern::Coloring {
+                    digit: 3,
+                    clusters: vec![
+                        [vec![(5, 3)], vec![(5, 5)]],
+                        [vec![(2, 0), (6, 2)], vec![(6, 3), (8, 0)]],
+                    ],
+                },
+                eliminations: vec![Candidate::new(2, 5, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn nothing_without_conjugate_pairs() {
+        let grid = SudokuGrid::new();
+        assert_eq!(grid.find_color_wrap(), None);
+        assert_eq!(grid.find_color_trap(), None);
+        assert_eq!(grid.find_multi_coloring(), None);
+    }
+}
@@ -0,0 +1,893 @@
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_observer::{SolverEvent, SolverObserver};
+use super::sudoku_units::{col_of, row_of, sees, COL_UNIT, ROW_UNIT, SUBGRID_UNIT, UNITS};
+use super::sudoku_values::SudokuValues;
+use std::fmt;
+use std::iter::FromIterator;
+
+//Deductions beyond the singles. Each technique looks for a pattern in the possible values of
+//the unsolved cells, and if it finds one that rules values out, reports it as a `Deduction`.
+//The patterns themselves live in their own modules, this one holds what they have in common.
+//Rows and columns are zero based, the same as the rest of the API, and only shown one based.
+
+///How a value was worked out, or ruled out, by deduction.
+///Listed from the easiest to the hardest, which is the order the solver tries them in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    ///The cell only had one possible value left
+    NakedSingle,
+    ///The value had only one possible place left in the cell's row, column or subgrid
+    HiddenSingle,
+    ///A digit that can only go in one row or column of a subgrid, so it can't go anywhere else
+    ///on that line
+    Pointing,
+    ///A digit that can only go in one subgrid's part of a row or column, so it can't go anywhere
+    ///else in the subgrid
+    BoxLineReduction,
+    ///Two cells of a unit that can only hold the same two values
+    NakedPair,
+    ///Two values that can only go in the same two cells of a unit
+    HiddenPair,
+    ///Three cells of a unit that can only hold the same three values between them
+    NakedTriple,
+    ///Three values that can only go in the same three cells of a unit
+    HiddenTriple,
+    ///Four cells of a unit that can only hold the same four values between them
+    NakedQuad,
+    ///Four values that can only go in the same four cells of a unit
+    HiddenQuad,
+    ///A digit that can only go in the same two columns of two rows, or the other way around
+    XWing,
+    ///A digit that can only go in the same three columns of three rows, or the other way around
+    Swordfish,
+    ///A digit that can only go in the same four columns of four rows, or the other way around
+    Jellyfish,
+    ///Three corners of a rectangle across two subgrids can only be a or b, so the fourth can't
+    ///be either. This and the other uniqueness techniques only hold for puzzles with one
+    ///solution.
+    UniqueRectangleType1,
+    ///Two corners of a unique rectangle next to each other can also be c, and only c
+    UniqueRectangleType2,
+    ///The extra values of two corners of a unique rectangle form a naked subset with other cells
+    UniqueRectangleType3,
+    ///Two corners of a unique rectangle next to each other hold the only places for a in a unit
+    UniqueRectangleType4,
+    ///Two opposite corners, or three corners, of a unique rectangle can also be c, and only c
+    UniqueRectangleType5,
+    ///The only places for a in both rows of a unique rectangle are at its corners
+    UniqueRectangleType6,
+    ///A unique rectangle where a is locked to the corners in the row and column of one corner
+    HiddenUniqueRectangle,
+    ///Every empty cell can only be two values, apart from one that can be three
+    BugPlusOne,
+    ///Two strong links on a digit in parallel rows or columns, with one end of each on the same
+    ///line
+    Skyscraper,
+    ///A strong link on a digit in a row and one in a column, with one end of each in the same
+    ///subgrid
+    TwoStringKite,
+    ///Two strong links on a digit with one end of each seeing the other
+    TurbotFish,
+    ///A subgrid where the digit can only go on one row and one column, together with a strong
+    ///link on a line outside it
+    EmptyRectangle,
+    ///A cell that can only be x or y, seeing one that can only be x or z and one that can only
+    ///be y or z
+    XYWing,
+    ///A cell that can only be x, y or z, seeing one that can only be x or z and one that can
+    ///only be y or z
+    XYZWing,
+    ///Two cells that can only be x or z, joined by a row, column or subgrid where x only has
+    ///two places
+    WWing,
+    ///Two cells of the same color, in a coloring of a digit's conjugate pairs, that see each
+    ///other
+    ColorWrap,
+    ///A cell that sees both colors of a coloring of a digit's conjugate pairs
+    ColorTrap,
+    ///Two colorings of a digit's conjugate pairs, where a color of one sees a color of the other
+    MultiColoring,
+    ///An X-Wing with extra places for the digit, all in one subgrid
+    FinnedXWing,
+    ///A finned X-Wing that would be missing a corner without its fins
+    SashimiXWing,
+    ///A Swordfish with extra places for the digit, all in one subgrid
+    FinnedSwordfish,
+    ///A finned Swordfish that would be missing a corner without its fins
+    SashimiSwordfish,
+    ///A Jellyfish with extra places for the digit, all in one subgrid
+    FinnedJellyfish,
+    ///A finned Jellyfish that would be missing a corner without its fins
+    SashimiJellyfish,
+    ///An alternating chain of strong and weak links on one digit
+    XChain,
+    ///A chain through cells that can only be two values, starting and ending on the same digit
+    XYChain,
+    ///An alternating inference chain, mixing links on a digit, links inside a cell and grouped
+    ///nodes
+    AlternatingInferenceChain,
+    ///Two almost locked sets with a restricted common candidate
+    AlsXz,
+    ///Two almost locked sets, each with a different restricted common candidate with a third
+    AlsXyWing,
+    ///A cell with an almost locked set for each of its possible values, whose places for that
+    ///value all see the cell
+    DeathBlossom,
+}
+
+impl Technique {
+    ///Every technique, easiest first
+    pub const ALL: [Technique; 43] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::Pointing,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::NakedQuad,
+        Technique::HiddenQuad,
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::Jellyfish,
+        Technique::UniqueRectangleType1,
+        Technique::UniqueRectangleType2,
+        Technique::UniqueRectangleType3,
+        Technique::UniqueRectangleType4,
+        Technique::UniqueRectangleType5,
+        Technique::UniqueRectangleType6,
+        Technique::HiddenUniqueRectangle,
+        Technique::BugPlusOne,
+        Technique::Skyscraper,
+        Technique::TwoStringKite,
+        Technique::TurbotFish,
+        Technique::EmptyRectangle,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::WWing,
+        Technique::ColorWrap,
+        Technique::ColorTrap,
+        Technique::MultiColoring,
+        Technique::FinnedXWing,
+        Technique::SashimiXWing,
+        Technique::FinnedSwordfish,
+        Technique::SashimiSwordfish,
+        Technique::FinnedJellyfish,
+        Technique::SashimiJellyfish,
+        Technique::XChain,
+        Technique::XYChain,
+        Technique::AlternatingInferenceChain,
+        Technique::AlsXz,
+        Technique::AlsXyWing,
+        Technique::DeathBlossom,
+    ];
+
+    ///Whether the technique relies on the puzzle having only one solution. These are the unique
+    ///rectangles and BUG+1, which rule out whatever would leave the puzzle with two.
+    pub fn needs_unique_solution(&self) -> bool {
+        return matches!(
+            self,
+            Technique::UniqueRectangleType1
+                | Technique::UniqueRectangleType2
+                | Technique::UniqueRectangleType3
+                | Technique::UniqueRectangleType4
+                | Technique::UniqueRectangleType5
+                | Technique::UniqueRectangleType6
+                | Technique::HiddenUniqueRectangle
+                | Technique::BugPlusOne
+        );
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Technique::NakedSingle => write!(f, "naked single"),
+            Technique::HiddenSingle => write!(f, "hidden single"),
+            Technique::Pointing => write!(f, "pointing"),
+            Technique::BoxLineReduction => write!(f, "box/line reduction"),
+            Technique::NakedPair => write!(f, "naked pair"),
+            Technique::HiddenPair => write!(f, "hidden pair"),
+            Technique::NakedTriple => write!(f, "naked triple"),
+            Technique::HiddenTriple => write!(f, "hidden triple"),
+            Technique::NakedQuad => write!(f, "naked quad"),
+            Technique::HiddenQuad => write!(f, "hidden quad"),
+            Technique::XWing => write!(f, "X-Wing"),
+            Technique::Swordfish => write!(f, "Swordfish"),
+            Technique::Jellyfish => write!(f, "Jellyfish"),
+            Technique::UniqueRectangleType1 => write!(f, "Unique Rectangle type 1"),
+            Technique::UniqueRectangleType2 => write!(f, "Unique Rectangle type 2"),
+            Technique::UniqueRectangleType3 => write!(f, "Unique Rectangle type 3"),
+            Technique::UniqueRectangleType4 => write!(f, "Unique Rectangle type 4"),
+            Technique::UniqueRectangleType5 => write!(f, "Unique Rectangle type 5"),
+            Technique::UniqueRectangleType6 => write!(f, "Unique Rectangle type 6"),
+            Technique::HiddenUniqueRectangle => write!(f, "hidden Unique Rectangle"),
+            Technique::BugPlusOne => write!(f, "BUG+1"),
+            Technique::Skyscraper => write!(f, "Skyscraper"),
+            Technique::TwoStringKite => write!(f, "2-String Kite"),
+            Technique::TurbotFish => write!(f, "Turbot Fish"),
+            Technique::EmptyRectangle => write!(f, "Empty Rectangle"),
+            Technique::XYWing => write!(f, "XY-Wing"),
+            Technique::XYZWing => write!(f, "XYZ-Wing"),
+            Technique::WWing => write!(f, "W-Wing"),
+            Technique::ColorWrap => write!(f, "color wrap"),
+            Technique::ColorTrap => write!(f, "color trap"),
+            Technique::MultiColoring => write!(f, "multi-coloring"),
+            Technique::FinnedXWing => write!(f, "finned X-Wing"),
+            Technique::SashimiXWing => write!(f, "sashimi X-Wing"),
+            Technique::FinnedSwordfish => write!(f, "finned Swordfish"),
+            Technique::SashimiSwordfish => write!(f, "sashimi Swordfish"),
+            Technique::FinnedJellyfish => write!(f, "finned Jellyfish"),
+            Technique::SashimiJellyfish => write!(f, "sashimi Jellyfish"),
+            Technique::XChain => write!(f, "X-Chain"),
+            Technique::XYChain => write!(f, "XY-Chain"),
+            Technique::AlternatingInferenceChain => write!(f, "AIC"),
+            Technique::AlsXz => write!(f, "ALS-XZ"),
+            Technique::AlsXyWing => write!(f, "ALS-XY-Wing"),
+            Technique::DeathBlossom => write!(f, "Death Blossom"),
+        };
+    }
+}
+
+///A set of techniques, stored as a bitmask the same way `SudokuValues` stores digits.
+///
+///The singles are always used, whether they are in the set or not, since every other technique
+///relies on them to place the values it narrows a cell down to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TechniqueSet {
+    bits: u64,
+}
+
+impl TechniqueSet {
+    pub fn empty() -> Self {
+        return TechniqueSet { bits: 0 };
+    }
+
+    ///Just the naked and hidden singles, which the solver always uses anyway
+    pub fn singles() -> Self {
+        return [Technique::NakedSingle, Technique::HiddenSingle]
+            .iter()
+            .cloned()
+            .collect();
+    }
+
+    ///Every technique, including the ones that need a unique solution
+    pub fn all() -> Self {
+        return Technique::ALL.iter().cloned().collect();
+    }
+
+    ///Every technique that holds whatever the number of solutions, which leaves out the ones
+    ///`Technique::needs_unique_solution` is true for
+    pub fn safe() -> Self {
+        return Technique::ALL
+            .iter()
+            .cloned()
+            .filter(|t| !t.needs_unique_solution())
+            .collect();
+    }
+
+    pub fn contains(&self, technique: Technique) -> bool {
+        return self.bits & TechniqueSet::bit(technique) != 0;
+    }
+
+    pub fn insert(&mut self, technique: Technique) {
+        self.bits |= TechniqueSet::bit(technique);
+    }
+
+    pub fn remove(&mut self, technique: Technique) {
+        self.bits &= !TechniqueSet::bit(technique);
+    }
+
+    ///The techniques in the set, easiest first
+    pub fn iter(&self) -> impl Iterator<Item = Technique> {
+        let set = *self;
+        return Technique::ALL
+            .iter()
+            .cloned()
+            .filter(move |t| set.contains(*t));
+    }
+
+    fn bit(technique: Technique) -> u64 {
+        return 1 << (technique as u64);
+    }
+}
+
+///Just the singles. Guessing is far quicker than looking for anything harder, so the other
+///techniques are only worth it when the steps matter, as when rating a puzzle.
+impl Default for TechniqueSet {
+    fn default() -> Self {
+        return TechniqueSet::singles();
+    }
+}
+
+impl FromIterator<Technique> for TechniqueSet {
+    fn from_iter<I: IntoIterator<Item = Technique>>(iter: I) -> Self {
+        let mut set = TechniqueSet::empty();
+        for technique in iter {
+            set.insert(technique);
+        }
+        return set;
+    }
+}
+
+impl fmt::Debug for TechniqueSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.debug_set().entries(self.iter()).finish();
+    }
+}
+
+///A row, column or subgrid, each of which has to hold every digit exactly once
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Subgrid(usize),
+}
+
+impl Unit {
+    ///The unit with the given index into the `UNITS` table
+    pub(crate) fn from_index(unit: usize) -> Unit {
+        return if unit >= SUBGRID_UNIT {
+            Unit::Subgrid(unit - SUBGRID_UNIT)
+        } else if unit >= COL_UNIT {
+            Unit::Column(unit - COL_UNIT)
+        } else {
+            Unit::Row(unit - ROW_UNIT)
+        };
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Unit::Row(r) => write!(f, "row {}", r + 1),
+            Unit::Column(c) => write!(f, "column {}", c + 1),
+            Unit::Subgrid(s) => write!(f, "subgrid {}", s + 1),
+        };
+    }
+}
+
+///A value that is still possible for a cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Candidate {
+    pub row: usize,
+    pub col: usize,
+    pub value: usize,
+}
+
+impl Candidate {
+    pub(crate) fn at(index: usize, value: usize) -> Candidate {
+        return Candidate {
+            row: row_of(index),
+            col: col_of(index),
+            value,
+        };
+    }
+}
+
+#[cfg(test)]
+impl Candidate {
+    pub(crate) fn new(row: usize, col: usize, value: usize) -> Candidate {
+        return Candidate { row, col, value };
+    }
+}
+
+///What a technique found, in enough detail to explain it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    ///`cells` of `unit` are the only ones that can hold `digits`, and hold nothing else
+    Subset {
+        unit: Unit,
+        cells: Vec<(usize, usize)>,
+        digits: SudokuValues,
+    },
+    ///Every place left for `digit` in `from` is also in `to`
+    LockedCandidates { digit: usize, from: Unit, to: Unit },
+    ///Every place left for `digit` in the `base` lines is in one of the `cover` lines, apart
+    ///from the `fins`
+    Fish {
+        digit: usize,
+        base: Vec<Unit>,
+        cover: Vec<Unit>,
+        fins: Vec<(usize, usize)>,
+    },
+    ///One of the `pincers` has to be `digit`, whatever the `pivot` turns out to be. The pivot
+    ///is one cell, apart from a W-Wing, where it is the two cells of the strong link.
+    Wing {
+        pivot: Vec<(usize, usize)>,
+        pincers: Vec<(usize, usize)>,
+        digit: usize,
+    },
+    ///A chain of strong links on `digit`, in order. Consecutive links are joined by ends that
+    ///see each other.
+    StrongLinks {
+        digit: usize,
+        links: Vec<StrongLink>,
+    },
+    ///Clusters of `digit`'s conjugate pairs, each as the cells of its two colors. In every
+    ///cluster one color holds the digit in all of its cells, and the other in none.
+    Coloring {
+        digit: usize,
+        clusters: Vec<[Vec<(usize, usize)>; 2]>,
+    },
+    ///An alternating inference chain. The links between the nodes alternate strong and weak,
+    ///starting and ending with a strong one, so either the first node or the last is true.
+    Chain { nodes: Vec<ChainNode> },
+    ///Four corners of a rectangle across two subgrids that can all be both `digits`, which would
+    ///let the puzzle have two solutions if they ended up as only those digits
+    UniqueRectangle {
+        corners: Vec<(usize, usize)>,
+        digits: SudokuValues,
+    },
+    ///Every empty cell can only be one of two values apart from `cell`, which has to be `digit`
+    Bug { cell: (usize, usize), digit: usize },
+    ///Almost locked sets joined by restricted common candidates. For ALS-XZ the two sets share
+    ///the digit in `links`. For an ALS-XY-Wing the last set is the pivot, sharing the first
+    ///digit in `links` with the first set and the second with the second. For a Death Blossom
+    ///each set is the petal for the `stem`'s value at the same position in `links`.
+    AlmostLockedSets {
+        stem: Option<(usize, usize)>,
+        sets: Vec<AlmostLockedSet>,
+        links: Vec<usize>,
+    },
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Pattern::Subset {
+                unit,
+                cells,
+                digits,
+            } => write!(
+                f,
+                "{} in {} ({})",
+                digit_list(*digits),
+                unit,
+                cell_list(cells)
+            ),
+            Pattern::LockedCandidates { digit, from, to } => {
+                write!(f, "{} in {} is locked to {}", digit, from, to)
+            }
+            Pattern::Fish {
+                digit,
+                base,
+                cover,
+                fins,
+            } => {
+                write!(
+                    f,
+                    "{} in {} covered by {}",
+                    digit,
+                    unit_list(base),
+                    unit_list(cover)
+                )?;
+                if !fins.is_empty() {
+                    write!(f, " with fins {}", cell_list(fins))?;
+                }
+                Ok(())
+            }
+            Pattern::Wing {
+                pivot,
+                pincers,
+                digit,
+            } => write!(
+                f,
+                "pivot {}, pincers {}, one of which is {}",
+                cell_list(pivot),
+                cell_list(pincers),
+                digit
+            ),
+            Pattern::StrongLinks { digit, links } => {
+                write!(f, "{} on ", digit)?;
+                for (i, link) in links.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{}{}", separator, link)?;
+                }
+                Ok(())
+            }
+            Pattern::Coloring { digit, clusters } => {
+                write!(f, "{} colored ", digit)?;
+                for (i, cluster) in clusters.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { "; " };
+                    write!(
+                        f,
+                        "{}{} / {}",
+                        separator,
+                        cell_list(&cluster[0]),
+                        cell_list(&cluster[1])
+                    )?;
+                }
+                Ok(())
+            }
+            Pattern::Chain { .. } => write!(f, "{}", self.eureka().unwrap_or_default()),
+            Pattern::UniqueRectangle { corners, digits } => {
+                write!(f, "{} in {}", digit_list(*digits), cell_list(corners))
+            }
+            Pattern::Bug { cell, digit } => {
+                write!(f, "{} has to be {}", cell_list(&[*cell]), digit)
+            }
+            Pattern::AlmostLockedSets { stem, sets, links } => {
+                if let Some(stem) = stem {
+                    write!(f, "stem {}: ", cell_list(&[*stem]))?;
+                }
+                for (i, set) in sets.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { " / " };
+                    write!(f, "{}{}", separator, set)?;
+                }
+                let links = links.iter().map(|d| d.to_string()).collect::<Vec<String>>();
+                write!(f, ", linked by {}", links.join(" and "))
+            }
+        };
+    }
+}
+
+impl Pattern {
+    ///The chain in Eureka notation, such as `(5)r1c2=(5)r1c7-(5)r4c7=(5)r4c2`, with `=` for a
+    ///strong link and `-` for a weak one. Returns `None` for patterns that aren't chains.
+    pub fn eureka(&self) -> Option<String> {
+        return match self {
+            Pattern::Chain { nodes } => {
+                let mut eureka = String::new();
+                for (i, node) in nodes.iter().enumerate() {
+                    if i > 0 {
+                        eureka.push(if i % 2 == 1 { '=' } else { '-' });
+                    }
+                    eureka.push_str(&node.to_string());
+                }
+                Some(eureka)
+            }
+            _ => None,
+        };
+    }
+}
+
+///A node of a chain: `digit` is in one of the `cells`. There is more than one cell for a grouped
+///node, where the cells all lie where a row or column meets a subgrid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainNode {
+    pub digit: usize,
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl fmt::Display for ChainNode {
+    ///Writes a grouped node the Eureka way, such as `(5)r1c23` for r1c2 and r1c3
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = String::new();
+        let mut cols = String::new();
+        for (row, col) in self.cells.iter() {
+            let (row, col) = ((row + 1).to_string(), (col + 1).to_string());
+            if !rows.contains(&row) {
+                rows.push_str(&row);
+            }
+            if !cols.contains(&col) {
+                cols.push_str(&col);
+            }
+        }
+        return write!(f, "({})r{}c{}", self.digit, rows, cols);
+    }
+}
+
+///Cells of a unit that, between them, can only be one more digit than there are cells
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlmostLockedSet {
+    pub unit: Unit,
+    pub cells: Vec<(usize, usize)>,
+    pub digits: SudokuValues,
+}
+
+impl fmt::Display for AlmostLockedSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "{} in {} ({})",
+            digit_list(self.digits),
+            cell_list(&self.cells),
+            self.unit
+        );
+    }
+}
+
+///A unit where a digit only has two places left, or two groups of places, so if one isn't the
+///digit the other has to be
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StrongLink {
+    pub unit: Unit,
+    pub first: Vec<(usize, usize)>,
+    pub second: Vec<(usize, usize)>,
+}
+
+impl fmt::Display for StrongLink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "{} = {} ({})",
+            cell_list(&self.first),
+            cell_list(&self.second),
+            self.unit
+        );
+    }
+}
+
+///One step of logic: the pattern a technique found, and the possible values it rules out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deduction {
+    pub technique: Technique,
+    pub pattern: Pattern,
+    pub eliminations: Vec<Candidate>,
+}
+
+impl fmt::Display for Deduction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} =>", self.technique, self.pattern)?;
+        for (i, c) in self.eliminations.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}r{}c{}<>{}", separator, c.row + 1, c.col + 1, c.value)?;
+        }
+        return Ok(());
+    }
+}
+
+impl SudokuGrid {
+    ///Looks for the easiest step of logic, among the given techniques, that rules out at least
+    ///one possible value. The singles are left to the solver, so this only finds anything once
+    ///they have all been placed.
+    ///
+    ///Techniques that need a unique solution are looked for like any other when they are in the
+    ///set. `TechniqueSet::safe()` leaves them out, so only pass `TechniqueSet::all()` when the
+    ///puzzle is known to have only one solution.
+    pub fn find_deduction(&self, techniques: TechniqueSet) -> Option<Deduction> {
+        //Built by the first of the chains, or of the ALS techniques, to need them, and shared with
+        //the others
+        let mut chain_graph = None;
+        let mut als_graph = None;
+        for technique in techniques.iter() {
+            let deduction = match technique {
+                Technique::NakedSingle | Technique::HiddenSingle => None,
+                Technique::Pointing => self.find_pointing(),
+                Technique::BoxLineReduction => self.find_box_line_reduction(),
+                Technique::NakedPair => self.find_naked_subset(2),
+                Technique::HiddenPair => self.find_hidden_subset(2),
+                Technique::NakedTriple => self.find_naked_subset(3),
+                Technique::HiddenTriple => self.find_hidden_subset(3),
+                Technique::NakedQuad => self.find_naked_subset(4),
+                Technique::HiddenQuad => self.find_hidden_subset(4),
+                Technique::XWing => self.find_fish(2),
+                Technique::Swordfish => self.find_fish(3),
+                Technique::Jellyfish => self.find_fish(4),
+                Technique::UniqueRectangleType1 => self.find_unique_rectangle_type_1(),
+                Technique::UniqueRectangleType2 => self.find_unique_rectangle_type_2(),
+                Technique::UniqueRectangleType3 => self.find_unique_rectangle_type_3(),
+                Technique::UniqueRectangleType4 => self.find_unique_rectangle_type_4(),
+                Technique::UniqueRectangleType5 => self.find_unique_rectangle_type_5(),
+                Technique::UniqueRectangleType6 => self.find_unique_rectangle_type_6(),
+                Technique::HiddenUniqueRectangle => self.find_hidden_unique_rectangle(),
+                Technique::BugPlusOne => self.find_bug_plus_one(),
+                Technique::Skyscraper => self.find_skyscraper(),
+                Technique::TwoStringKite => self.find_two_string_kite(),
+                Technique::TurbotFish => self.find_turbot_fish(),
+                Technique::EmptyRectangle => self.find_empty_rectangle(),
+                Technique::XYWing => self.find_xy_wing(),
+                Technique::XYZWing => self.find_xyz_wing(),
+                Technique::WWing => self.find_w_wing(),
+                Technique::ColorWrap => self.find_color_wrap(),
+                Technique::ColorTrap => self.find_color_trap(),
+                Technique::MultiColoring => self.find_multi_coloring(),
+                Technique::FinnedXWing => self.find_finned_fish(2, false),
+                Technique::SashimiXWing => self.find_finned_fish(2, true),
+                Technique::FinnedSwordfish => self.find_finned_fish(3, false),
+                Technique::SashimiSwordfish => self.find_finned_fish(3, true),
+                Technique::FinnedJellyfish => self.find_finned_fish(4, false),
+                Technique::SashimiJellyfish => self.find_finned_fish(4, true),
+                Technique::XChain => {
+                    self.find_x_chain(chain_graph.get_or_insert_with(|| self.chain_graph()))
+                }
+                Technique::XYChain => {
+                    self.find_xy_chain(chain_graph.get_or_insert_with(|| self.chain_graph()))
+                }
+                Technique::AlternatingInferenceChain => self
+                    .find_alternating_chain(chain_graph.get_or_insert_with(|| self.chain_graph())),
+                Technique::AlsXz => {
+                    self.find_als_xz(als_graph.get_or_insert_with(|| self.als_graph()))
+                }
+                Technique::AlsXyWing => {
+                    self.find_als_xy_wing(als_graph.get_or_insert_with(|| self.als_graph()))
+                }
+                Technique::DeathBlossom => {
+                    self.find_death_blossom(als_graph.get_or_insert_with(|| self.als_graph()))
+                }
+            };
+            if deduction.is_some() {
+                return deduction;
+            }
+        }
+        return None;
+    }
+
+    ///Takes the ruled out values out of their cells, telling the observer about the deduction
+    ///and each value it rules out
+    pub(crate) fn apply_deduction(
+        &mut self,
+        deduction: &Deduction,
+        observer: &mut dyn SolverObserver,
+    ) {
+        observer.on_event(&SolverEvent::Deduction(deduction.clone()));
+        for c in deduction.eliminations.iter() {
+            if self.candidates_at(c.row * 9 + c.col).contains(c.value) {
+                observer.on_event(&SolverEvent::Elimination {
+                    row: c.row,
+                    col: c.col,
+                    value: c.value,
+                });
+                self.remove_candidate(c.row * 9 + c.col, c.value);
+            }
+        }
+    }
+
+    ///The cells of the unit where the digit is still possible
+    pub(crate) fn places_in(&self, unit: usize, digit: usize) -> Vec<usize> {
+        return UNITS[unit]
+            .iter()
+            .cloned()
+            .filter(|i| self.candidates_at(*i).contains(digit))
+            .collect();
+    }
+
+    ///Every other cell that could be the digit, and sees all of the given cells. If one of the
+    ///given cells has to be the digit, none of these can be.
+    pub(crate) fn seen_by_all(&self, cells: &[usize], digit: usize) -> Vec<Candidate> {
+        return (0..81)
+            .filter(|i| !cells.contains(i) && self.candidates_at(*i).contains(digit))
+            .filter(|i| cells.iter().all(|c| sees(*i, *c)))
+            .map(|i| Candidate::at(i, digit))
+            .collect();
+    }
+
+    ///The possible values of the cell, empty once it has a value
+    pub(crate) fn candidates_at(&self, index: usize) -> SudokuValues {
+        return *self.cells()[index].candidates();
+    }
+}
+
+///Every way of picking `size` of the items, keeping them in order
+pub(crate) fn combinations<T: Copy>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        if items.len() - i < size {
+            break;
+        }
+        for rest in combinations(&items[i + 1..], size - 1) {
+            let mut picked = vec![*item];
+            picked.extend(rest);
+            result.push(picked);
+        }
+    }
+    return result;
+}
+
+///The zero based row and column of each cell index
+pub(crate) fn positions(indexes: &[usize]) -> Vec<(usize, usize)> {
+    return indexes.iter().map(|i| (row_of(*i), col_of(*i))).collect();
+}
+
+///Digits written one after another, such as "137"
+pub(crate) fn digit_list(digits: SudokuValues) -> String {
+    return digits.iter().map(|d| d.to_string()).collect();
+}
+
+///Cells written as "r1c2, r1c5"
+pub(crate) fn cell_list(cells: &[(usize, usize)]) -> String {
+    return cells
+        .iter()
+        .map(|(r, c)| format!("r{}c{}", r + 1, c + 1))
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+///Units written as "row 1, row 5"
+pub(crate) fn unit_list(units: &[Unit]) -> String {
+    return units
+        .iter()
+        .map(|u| u.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_observer::NoObserver;
+
+    #[test]
+    fn technique_sets() {
+        assert_eq!(TechniqueSet::default(), TechniqueSet::singles());
+        assert_eq!(TechniqueSet::all().iter().count(), 43);
+        assert!(TechniqueSet::all()
+            .iter()
+            .eq(Technique::ALL.iter().cloned()));
+        let safe = TechniqueSet::safe();
+        assert!(!safe.contains(Technique::UniqueRectangleType1));
+        assert!(!safe.contains(Technique::BugPlusOne));
+        assert!(safe.contains(Technique::DeathBlossom));
+        assert_eq!(safe.iter().count(), 43 - 8);
+
+        let mut set = TechniqueSet::empty();
+        set.insert(Technique::XWing);
+        set.insert(Technique::NakedPair);
+        set.insert(Technique::XWing);
+        assert_eq!(
+            set.iter().collect::<Vec<Technique>>(),
+            vec![Technique::NakedPair, Technique::XWing]
+        );
+        set.remove(Technique::NakedPair);
+        assert_eq!(set, [Technique::XWing].iter().cloned().collect());
+        assert_eq!(format!("{:?}", set), "{XWing}");
+    }
+
+    #[test]
+    fn combinations_keep_their_order() {
+        assert_eq!(
+            combinations(&[1, 2, 3, 4], 3),
+            vec![vec![1, 2, 3], vec![1, 2, 4], vec![1, 3, 4], vec![2, 3, 4]]
+        );
+        assert_eq!(combinations(&[1, 2], 0), vec![Vec::<i32>::new()]);
+        assert!(combinations(&[1, 2], 3).is_empty());
+    }
+
+    #[test]
+    fn deductions_are_written_out() {
+        let deduction = Deduction {
+            technique: Technique::NakedPair,
+            pattern: Pattern::Subset {
+                unit: Unit::Row(3),
+                cells: vec![(3, 2), (3, 4)],
+                digits: SudokuValues::from_digits(&[3, 7]),
+            },
+            eliminations: vec![Candidate::new(3, 6, 7), Candidate::new(3, 7, 3)],
+        };
+        assert_eq!(
+            deduction.to_string(),
+            "naked pair: 37 in row 4 (r4c3, r4c5) => r4c7<>7, r4c8<>3"
+        );
+    }
+
+    #[test]
+    fn finds_the_easiest_step_and_applies_it() {
+        //r1c1 and r1c2 can only be 1 or 2, so nothing else in row 1 can be. Nothing easier is
+        //there to be found.
+        let mut grid = SudokuGrid::from_candidates(
+            "
+            12 12 123 . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            .  .  .   . . . . . .
+            ",
+        );
+        assert_eq!(grid.find_deduction(TechniqueSet::singles()), None);
+        let deduction = grid.find_deduction(TechniqueSet::safe()).unwrap();
+        assert_eq!(deduction.technique, Technique::NakedPair);
+        assert_eq!(
+            deduction.pattern,
+            Pattern::Subset {
+                unit: Unit::Row(0),
+                cells: vec![(0, 0), (0, 1)],
+                digits: SudokuValues::from_digits(&[1, 2]),
+            }
+        );
+        assert_eq!(deduction.eliminations.len(), 2 + 6 * 2);
+
+        grid.apply_deduction(&deduction, &mut NoObserver);
+        assert_eq!(grid.candidates_at(2), SudokuValues::from_digits(&[3]));
+        assert!(!grid.candidates_at(3).contains(1));
+        assert_eq!(grid.candidates_at(9), SudokuValues::all());
+    }
+}
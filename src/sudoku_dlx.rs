@@ -0,0 +1,366 @@
+use super::sudoku_error::SudokuError;
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_solver::SudokuSolver;
+use super::sudoku_units::{col_of, row_of, subgrid_of};
+
+//The exact cover matrix has one row for every (cell, digit) pair and one column for every
+//constraint a solution has to meet exactly once:
+//  0..81    every cell holds a digit
+//  81..162  every row holds every digit
+//  162..243 every column holds every digit
+//  243..324 every subgrid holds every digit
+const CONSTRAINTS: usize = 324;
+const ROOT: usize = 0;
+
+///Solves grids as an exact cover problem, using Knuth's Dancing Links (Algorithm X).
+///
+///This shares nothing with the deduction in `SudokuGrid` beyond reading the givens, which makes
+///it useful for checking the answers of the `BacktrackingSolver`, and it is quick at counting
+///solutions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DancingLinksSolver;
+
+impl DancingLinksSolver {
+    pub fn new() -> Self {
+        return DancingLinksSolver;
+    }
+
+    ///Runs the search, calling `on_solution` with the (cell index, value) pairs of every
+    ///solution found until it returns false
+    fn search<F: FnMut(&[(usize, usize)]) -> bool>(
+        &self,
+        grid: &SudokuGrid,
+        on_solution: F,
+    ) -> Result<(), SudokuError> {
+        let mut links = Links::new(grid)?;
+        let mut partial = Vec::with_capacity(81);
+        let mut on_solution = on_solution;
+        links.search(&mut partial, &mut on_solution);
+        return Ok(());
+    }
+}
+
+impl SudokuSolver for DancingLinksSolver {
+    ///Returns `SudokuError::Contradiction` if an empty cell has no value left that fits with
+    ///the givens, and `SudokuError::SearchExhausted` if there is no solution for any other
+    ///reason
+    fn solve(&self, grid: &SudokuGrid) -> Result<SudokuGrid, SudokuError> {
+        let mut solution = None;
+        self.search(grid, |placements| {
+            let mut solved = grid.clone();
+            for (index, value) in placements {
+                solved.place_value(*index, *value);
+            }
+            solution = Some(solved);
+            false
+        })?;
+
+        return solution.ok_or(SudokuError::SearchExhausted);
+    }
+
+    fn count_solutions(&self, grid: &SudokuGrid, limit: usize) -> usize {
+        let mut count = 0;
+        if limit == 0 {
+            return count;
+        }
+        //A grid that fails to set up has no solutions
+        let _ = self.search(grid, |_| {
+            count += 1;
+            count < limit
+        });
+        return count;
+    }
+}
+
+///The sparse exact cover matrix, as circular doubly linked lists held in vectors.
+///Index 0 is the root, 1 to 324 are the column headers and everything after that is a node.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    ///Number of nodes left in each column, indexed by header
+    size: Vec<usize>,
+    ///The (cell index, value) each node's matrix row stands for
+    placement: Vec<(usize, usize)>,
+}
+
+impl Links {
+    ///Builds the matrix for the empty cells of the grid. Values that clash with a given are
+    ///left out, and the constraints the givens already meet are left out with them.
+    fn new(grid: &SudokuGrid) -> Result<Links, SudokuError> {
+        let capacity = 1 + CONSTRAINTS + 729 * 4;
+        let mut links = Links {
+            left: Vec::with_capacity(capacity),
+            right: Vec::with_capacity(capacity),
+            up: Vec::with_capacity(capacity),
+            down: Vec::with_capacity(capacity),
+            column: Vec::with_capacity(capacity),
+            size: vec![0; 1 + CONSTRAINTS],
+            placement: Vec::with_capacity(capacity),
+        };
+
+        for header in 0..=CONSTRAINTS {
+            links
+                .left
+                .push(if header == 0 { CONSTRAINTS } else { header - 1 });
+            links
+                .right
+                .push(if header == CONSTRAINTS { 0 } else { header + 1 });
+            links.up.push(header);
+            links.down.push(header);
+            links.column.push(header);
+            links.placement.push((0, 0));
+        }
+
+        let mut met = [false; CONSTRAINTS];
+        for (index, cell) in grid.cells().iter().enumerate() {
+            if let Some(v) = cell.value() {
+                for constraint in constraints_of(index, v).iter() {
+                    met[*constraint] = true;
+                }
+            }
+        }
+
+        for (index, cell) in grid.cells().iter().enumerate() {
+            if cell.value().is_some() {
+                continue;
+            }
+            let mut any = false;
+            for value in 1..=9 {
+                let constraints = constraints_of(index, value);
+                if constraints.iter().any(|c| met[*c]) {
+                    continue;
+                }
+                links.add_row((index, value), &constraints);
+                any = true;
+            }
+            if !any {
+                return Err(SudokuError::Contradiction {
+                    row: row_of(index),
+                    col: col_of(index),
+                });
+            }
+        }
+
+        //Constraints the givens already meet don't need to be covered again
+        for (constraint, is_met) in met.iter().enumerate() {
+            if *is_met {
+                let header = constraint + 1;
+                links.right[links.left[header]] = links.right[header];
+                links.left[links.right[header]] = links.left[header];
+            }
+        }
+        return Ok(links);
+    }
+
+    fn add_row(&mut self, placement: (usize, usize), constraints: &[usize; 4]) {
+        let first = self.left.len();
+        for (i, constraint) in constraints.iter().enumerate() {
+            let node = first + i;
+            let header = constraint + 1;
+            self.left.push(if i == 0 { first + 3 } else { node - 1 });
+            self.right.push(if i == 3 { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.placement.push(placement);
+
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+    }
+
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    ///Algorithm X. Returns false once `on_solution` asks to stop.
+    fn search<F: FnMut(&[(usize, usize)]) -> bool>(
+        &mut self,
+        partial: &mut Vec<(usize, usize)>,
+        on_solution: &mut F,
+    ) -> bool {
+        if self.right[ROOT] == ROOT {
+            return on_solution(partial);
+        }
+
+        //Always branch on the constraint with the fewest ways of meeting it
+        let mut header = self.right[ROOT];
+        let mut best = header;
+        while header != ROOT {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+            header = self.right[header];
+        }
+        if self.size[best] == 0 {
+            return true;
+        }
+
+        self.cover(best);
+        let mut row = self.down[best];
+        let mut keep_going = true;
+        while row != best && keep_going {
+            partial.push(self.placement[row]);
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+
+            keep_going = self.search(partial, on_solution);
+
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+            partial.pop();
+            row = self.down[row];
+        }
+        self.uncover(best);
+        return keep_going;
+    }
+}
+
+fn constraints_of(index: usize, value: usize) -> [usize; 4] {
+    let digit = value - 1;
+    return [
+        index,
+        81 + row_of(index) * 9 + digit,
+        162 + col_of(index) * 9 + digit,
+        243 + subgrid_of(index) * 9 + digit,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_solver::BacktrackingSolver;
+
+    const ONE_SOLUTION: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    const HARD: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+    //The first puzzle with a few givens taken out
+    const SEVERAL_SOLUTIONS: &str =
+        ".61...8......5...384.76..5..5..7.36.7..3.5..1..4.2.....8..96.3.3...475...72......";
+    //The first puzzle with r1c1 given as 2 instead of being left empty. It clashes with no
+    //given directly, but leaves the puzzle without a solution.
+    const NO_SOLUTION: &str =
+        "261...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    //Row 1 holds 1 to 8, and column 9 already has a 9, so r1c9 has nothing left
+    const CLASH: &str =
+        "12345678.........9...............................................................";
+
+    fn grid(line: &str) -> SudokuGrid {
+        return SudokuGrid::parse_str(line).unwrap();
+    }
+
+    #[test]
+    fn same_solution_as_backtracking() {
+        for line in [ONE_SOLUTION, HARD].iter() {
+            let grid = grid(line);
+            let dlx = DancingLinksSolver::new().solve(&grid).unwrap();
+            let backtracking = BacktrackingSolver::new().solve(&grid).unwrap();
+            assert_eq!(dlx.to_line(), backtracking.to_line());
+            assert_eq!(DancingLinksSolver::new().count_solutions(&grid, 10), 1);
+            assert_eq!(BacktrackingSolver::new().count_solutions(&grid, 10), 1);
+        }
+    }
+
+    #[test]
+    fn same_count_as_backtracking() {
+        let grid = grid(SEVERAL_SOLUTIONS);
+        let dlx = DancingLinksSolver::new().count_solutions(&grid, 1000);
+        let backtracking = BacktrackingSolver::new().count_solutions(&grid, 1000);
+        assert!(dlx > 1);
+        assert_eq!(dlx, backtracking);
+
+        //Every solution listed fits the givens, and none is listed twice
+        let mut solutions = grid
+            .solutions()
+            .map(|s| s.to_line())
+            .collect::<Vec<String>>();
+        assert_eq!(solutions.len(), dlx);
+        for solution in solutions.iter() {
+            let solved = SudokuGrid::parse_str(solution).unwrap();
+            assert_eq!(DancingLinksSolver::new().count_solutions(&solved, 2), 1);
+            for (given, value) in SEVERAL_SOLUTIONS.chars().zip(solution.chars()) {
+                assert!(given == '.' || given == value);
+            }
+        }
+        solutions.sort();
+        solutions.dedup();
+        assert_eq!(solutions.len(), dlx);
+
+        let empty = SudokuGrid::parse_str(&".".repeat(81)).unwrap();
+        assert_eq!(DancingLinksSolver::new().count_solutions(&empty, 50), 50);
+        assert_eq!(BacktrackingSolver::new().count_solutions(&empty, 50), 50);
+    }
+
+    #[test]
+    fn no_solution() {
+        let grid = grid(NO_SOLUTION);
+        assert_eq!(DancingLinksSolver::new().count_solutions(&grid, 10), 0);
+        assert_eq!(BacktrackingSolver::new().count_solutions(&grid, 10), 0);
+        assert!(DancingLinksSolver::new()
+            .solve(&grid)
+            .unwrap_err()
+            .is_unsolvable());
+        assert!(BacktrackingSolver::new()
+            .solve(&grid)
+            .unwrap_err()
+            .is_unsolvable());
+    }
+
+    #[test]
+    fn clashing_given_is_a_contradiction() {
+        let grid = grid(CLASH);
+        for result in [
+            DancingLinksSolver::new().solve(&grid),
+            BacktrackingSolver::new().solve(&grid),
+        ]
+        .iter()
+        {
+            match result {
+                Err(SudokuError::Contradiction { row: 0, col: 8 }) => {}
+                other => panic!("expected a contradiction at r1c9, got {:?}", other),
+            }
+        }
+        assert_eq!(DancingLinksSolver::new().count_solutions(&grid, 10), 0);
+        assert_eq!(BacktrackingSolver::new().count_solutions(&grid, 10), 0);
+    }
+}
@@ -0,0 +1,198 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+///Everything that can go wrong while reading, editing or solving a grid.
+///Rows and columns are zero based, the same as the rest of the API; they are only shown one
+///based when the error is displayed.
+#[derive(Debug)]
+pub enum SudokuError {
+    ///The puzzle could not be read
+    Io(io::Error),
+    ///The input has more lines than the puzzle needs. `line` is the zero based line that was one
+    ///too many
+    TooManyLines { line: usize },
+    ///The input ended before all 9 rows were read
+    TooFewLines { found: usize },
+    ///A row has fewer than 9 comma delimited entries
+    TooFewColumns { row: usize, found: usize },
+    ///A single line puzzle that isn't 81 characters long
+    WrongLength { found: usize },
+    ///A number that isn't between 1 and 9
+    ValueOutOfRange {
+        row: usize,
+        col: usize,
+        token: String,
+    },
+    ///A row or column outside of the grid
+    InvalidPosition { row: usize, col: usize },
+    ///The cell already has a value
+    CellOccupied { row: usize, col: usize },
+    ///The value is already present in the cell's row, column or subgrid
+    ConflictingValue {
+        row: usize,
+        col: usize,
+        value: usize,
+    },
+    ///Deduction left a cell with no possible values, so the puzzle as given has no solution
+    Contradiction { row: usize, col: usize },
+    ///Every possibility was tried without finding a solution, so the puzzle has none
+    SearchExhausted,
+}
+
+impl SudokuError {
+    ///Returns true if the puzzle was read correctly, but has no solution
+    pub fn is_unsolvable(&self) -> bool {
+        return matches!(
+            self,
+            SudokuError::Contradiction { .. } | SudokuError::SearchExhausted
+        );
+    }
+}
+
+impl fmt::Display for SudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            SudokuError::Io(e) => write!(f, "Failed to read the puzzle: {}", e),
+            SudokuError::TooManyLines { line } => {
+                write!(
+                    f,
+                    "Too many lines: line {} is past the end of the puzzle",
+                    line + 1
+                )
+            }
+            SudokuError::TooFewLines { found } => {
+                write!(f, "Too few lines: expected 9 rows, found {}", found)
+            }
+            SudokuError::TooFewColumns { row, found } => {
+                write!(f, "Row {} must have 9 values, found {}", row + 1, found)
+            }
+            SudokuError::WrongLength { found } => write!(
+                f,
+                "A single line puzzle must have 81 characters, found {}",
+                found
+            ),
+            SudokuError::ValueOutOfRange { row, col, token } => write!(
+                f,
+                "Row {} Column {}: '{}' is not a number between 1 and 9",
+                row + 1,
+                col + 1,
+                token
+            ),
+            SudokuError::InvalidPosition { row, col } => write!(
+                f,
+                "Row {} Column {} is outside of the grid",
+                row + 1,
+                col + 1
+            ),
+            SudokuError::CellOccupied { row, col } => {
+                write!(f, "Row {} Column {} already has a value", row + 1, col + 1)
+            }
+            SudokuError::ConflictingValue { row, col, value } => write!(
+                f,
+                "Row {} Column {}: {} is already present in the cell's row, column or subgrid",
+                row + 1,
+                col + 1,
+                value
+            ),
+            SudokuError::Contradiction { row, col } => write!(
+                f,
+                "Row {} Column {} has no possible values, the puzzle has no solution",
+                row + 1,
+                col + 1
+            ),
+            SudokuError::SearchExhausted => write!(f, "Unable to solve this puzzle"),
+        };
+    }
+}
+
+impl Error for SudokuError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            SudokuError::Io(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for SudokuError {
+    fn from(e: io::Error) -> Self {
+        return SudokuError::Io(e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_grid::SudokuGrid;
+
+    #[test]
+    fn shown_one_based() {
+        let error = SudokuError::ValueOutOfRange {
+            row: 0,
+            col: 8,
+            token: "x".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Row 1 Column 9: 'x' is not a number between 1 and 9"
+        );
+        assert_eq!(
+            SudokuError::TooManyLines { line: 9 }.to_string(),
+            "Too many lines: line 10 is past the end of the puzzle"
+        );
+        assert_eq!(
+            SudokuError::Contradiction { row: 4, col: 2 }.to_string(),
+            "Row 5 Column 3 has no possible values, the puzzle has no solution"
+        );
+    }
+
+    #[test]
+    fn only_unsolvable_puzzles_are_unsolvable() {
+        assert!(SudokuError::SearchExhausted.is_unsolvable());
+        assert!(SudokuError::Contradiction { row: 0, col: 0 }.is_unsolvable());
+        assert!(!SudokuError::WrongLength { found: 80 }.is_unsolvable());
+        assert!(!SudokuError::CellOccupied { row: 0, col: 0 }.is_unsolvable());
+    }
+
+    #[test]
+    fn io_errors_keep_their_source() {
+        let error = SudokuError::from(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        assert!(matches!(error, SudokuError::Io(_)));
+        assert_eq!(error.source().unwrap().to_string(), "no such file");
+        assert!(SudokuError::SearchExhausted.source().is_none());
+    }
+
+    #[test]
+    fn set_value_reports_what_is_wrong() {
+        let mut grid = SudokuGrid::new();
+        assert!(matches!(
+            grid.set_value(9, 0, 1),
+            Err(SudokuError::InvalidPosition { row: 9, col: 0 })
+        ));
+        match grid.set_value(0, 0, 10) {
+            Err(SudokuError::ValueOutOfRange {
+                row: 0,
+                col: 0,
+                token,
+            }) => assert_eq!(token, "10"),
+            other => panic!("expected the value to be out of range, got {:?}", other),
+        }
+
+        grid.set_value(0, 0, 5).unwrap();
+        assert!(matches!(
+            grid.set_value(0, 0, 6),
+            Err(SudokuError::CellOccupied { row: 0, col: 0 })
+        ));
+        //Same subgrid as r1c1
+        assert!(matches!(
+            grid.set_value(2, 2, 5),
+            Err(SudokuError::ConflictingValue {
+                row: 2,
+                col: 2,
+                value: 5
+            })
+        ));
+        assert_eq!(grid.get_value(2, 2), None);
+    }
+}
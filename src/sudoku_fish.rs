@@ -0,0 +1,637 @@
+use super::sudoku_deduction::{
+    combinations, positions, Candidate, Deduction, Pattern, Technique, Unit,
+};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_subsets::{locked_subsets, subsets_within};
+use super::sudoku_units::{sees, COL_UNIT, ROW_UNIT, UNITS};
+use super::sudoku_values::SudokuValues;
+
+impl SudokuGrid {
+    ///A fish is `size` rows (the base sets) where a digit can only go in the same `size` columns
+    ///(the cover sets). Each base row needs the digit once, and they can only get it from the
+    ///cover columns, so between them they use up the digit in every cover column. Nothing else
+    ///in those columns can hold it. The same goes with rows and columns swapped.
+    ///An X-Wing has two of each, a Swordfish three and a Jellyfish four.
+    pub(crate) fn find_fish(&self, size: usize) -> Option<Deduction> {
+        for digit in 1..=9 {
+            for (base_start, cover_start) in [(ROW_UNIT, COL_UNIT), (COL_UNIT, ROW_UNIT)].iter() {
+                //Where the digit can go on each line, with the crossing line at position p stored
+                //as the digit p + 1. Lines where it has one place left are left to the singles.
+                let lines = (*base_start..*base_start + 9)
+                    .filter(|u| (2..=size).contains(&self.places_in(*u, digit).len()))
+                    .collect::<Vec<usize>>();
+                let sets = lines
+                    .iter()
+                    .map(|u| self.crossings(*u, digit))
+                    .collect::<Vec<SudokuValues>>();
+
+                for picked in locked_subsets(&sets, size) {
+                    let base = picked.iter().map(|p| lines[*p]).collect::<Vec<usize>>();
+                    let cover = picked
+                        .iter()
+                        .fold(SudokuValues::empty(), |c, p| c.union(sets[*p]))
+                        .iter()
+                        .map(|p| cover_start + p - 1)
+                        .collect::<Vec<usize>>();
+
+                    let eliminations = cover
+                        .iter()
+                        .flat_map(|u| UNITS[*u].iter())
+                        .filter(|i| !base.iter().any(|b| UNITS[*b].contains(i)))
+                        .filter(|i| self.candidates_at(**i).contains(digit))
+                        .map(|i| Candidate::at(*i, digit))
+                        .collect::<Vec<Candidate>>();
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: fish_technique(size),
+                            pattern: Pattern::Fish {
+                                digit,
+                                base: base.iter().map(|u| Unit::from_index(*u)).collect(),
+                                cover: cover.iter().map(|u| Unit::from_index(*u)).collect(),
+                                fins: Vec::new(),
+                            },
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///A finned fish is a fish with a few extra places for the digit in its base lines, the fins,
+    ///all in one subgrid. Either the digit goes in one of the fins, or the fish holds. Whichever
+    ///it is, the digit can't go in a cell of the cover lines that sees every fin.
+    ///
+    ///A sashimi fish is a finned fish where, without the fins, one of the base lines would only
+    ///have one place left, so it wouldn't be a fish at all. The same reasoning still holds.
+    pub(crate) fn find_finned_fish(&self, size: usize, sashimi: bool) -> Option<Deduction> {
+        for digit in 1..=9 {
+            for (base_start, cover_start) in [(ROW_UNIT, COL_UNIT), (COL_UNIT, ROW_UNIT)].iter() {
+                let lines = (*base_start..*base_start + 9)
+                    .filter(|u| self.places_in(*u, digit).len() >= 2)
+                    .collect::<Vec<usize>>();
+                let sets = lines
+                    .iter()
+                    .map(|u| self.crossings(*u, digit))
+                    .collect::<Vec<SudokuValues>>();
+
+                //Fins all in one subgrid can only add up to three more crossing lines
+                for picked in subsets_within(&sets, size, size + 3) {
+                    let base = picked.iter().map(|p| lines[*p]).collect::<Vec<usize>>();
+
+                    //The fins' subgrid is where one band of base lines meets one stack of
+                    //cover lines
+                    for band in 0..3 {
+                        let in_band = |p: &usize| (lines[*p] - base_start) / 3 == band;
+                        for stack in 0..3 {
+                            let mut stack_lines = SudokuValues::empty();
+                            for p in stack * 3 + 1..=stack * 3 + 3 {
+                                stack_lines.insert(p);
+                            }
+
+                            //Every place outside the fins' subgrid has to be covered
+                            let mut outside = SudokuValues::empty();
+                            let mut inside = SudokuValues::empty();
+                            for p in picked.iter() {
+                                if in_band(p) {
+                                    outside = outside.union(sets[*p].difference(stack_lines));
+                                    inside = inside.union(sets[*p].intersection(stack_lines));
+                                } else {
+                                    outside = outside.union(sets[*p]);
+                                }
+                            }
+                            if inside.is_empty() || outside.len() > size {
+                                continue;
+                            }
+                            //Only the cells of the fins' subgrid outside the base lines can
+                            //lose the digit, so don't bother with the cover if there are none
+                            let targets = (band * 3..band * 3 + 3)
+                                .filter(|l| !base.contains(&(base_start + l)))
+                                .flat_map(|l| {
+                                    UNITS[base_start + l][stack * 3..stack * 3 + 3].iter()
+                                })
+                                .any(|i| self.candidates_at(*i).contains(digit));
+                            if !targets {
+                                continue;
+                            }
+
+                            //The rest of the cover comes from the lines crossing the subgrid
+                            let extra = inside.difference(outside).iter().collect::<Vec<usize>>();
+                            if outside.len() + extra.len() < size {
+                                continue;
+                            }
+                            for added in combinations(&extra, size - outside.len()) {
+                                let mut cover_set = outside;
+                                for p in added {
+                                    cover_set.insert(p);
+                                }
+                                let cover = cover_set
+                                    .iter()
+                                    .map(|p| cover_start + p - 1)
+                                    .collect::<Vec<usize>>();
+                                if let Some(deduction) =
+                                    self.finned_fish(digit, &base, &cover, sashimi)
+                                {
+                                    return Some(deduction);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Checks a finned fish with the given base and cover lines, and works out what it rules out
+    fn finned_fish(
+        &self,
+        digit: usize,
+        base: &[usize],
+        cover: &[usize],
+        sashimi: bool,
+    ) -> Option<Deduction> {
+        let places = base
+            .iter()
+            .flat_map(|u| self.places_in(*u, digit))
+            .collect::<Vec<usize>>();
+        let (body, fins): (Vec<usize>, Vec<usize>) = places
+            .iter()
+            .partition(|i| cover.iter().any(|u| UNITS[*u].contains(i)));
+        if fins.is_empty() {
+            return None;
+        }
+
+        //How many places each base line has without the fins
+        let body_counts = base
+            .iter()
+            .map(|u| body.iter().filter(|i| UNITS[*u].contains(i)).count())
+            .collect::<Vec<usize>>();
+        if body_counts.contains(&0) || body_counts.contains(&1) != sashimi {
+            return None;
+        }
+
+        let eliminations = cover
+            .iter()
+            .flat_map(|u| UNITS[*u].iter())
+            .filter(|i| !places.contains(i))
+            .filter(|i| self.candidates_at(**i).contains(digit))
+            .filter(|i| fins.iter().all(|f| sees(**i, *f)))
+            .map(|i| Candidate::at(*i, digit))
+            .collect::<Vec<Candidate>>();
+        if eliminations.is_empty() {
+            return None;
+        }
+        return Some(Deduction {
+            technique: finned_fish_technique(base.len(), sashimi),
+            pattern: Pattern::Fish {
+                digit,
+                base: base.iter().map(|u| Unit::from_index(*u)).collect(),
+                cover: cover.iter().map(|u| Unit::from_index(*u)).collect(),
+                fins: positions(&fins),
+            },
+            eliminations,
+        });
+    }
+
+    ///The positions along a row or column where the digit can go, as the digits 1 to 9
+    fn crossings(&self, line: usize, digit: usize) -> SudokuValues {
+        let mut positions = SudokuValues::empty();
+        for (p, i) in UNITS[line].iter().enumerate() {
+            if self.candidates_at(*i).contains(digit) {
+                positions.insert(p + 1);
+            }
+        }
+        return positions;
+    }
+}
+
+fn fish_technique(size: usize) -> Technique {
+    return match size {
+        2 => Technique::XWing,
+        3 => Technique::Swordfish,
+        _ => Technique::Jellyfish,
+    };
+}
+
+fn finned_fish_technique(size: usize, sashimi: bool) -> Technique {
+    return match (size, sashimi) {
+        (2, false) => Technique::FinnedXWing,
+        (2, true) => Technique::SashimiXWing,
+        (3, false) => Technique::FinnedSwordfish,
+        (3, true) => Technique::SashimiSwordfish,
+        (_, false) => Technique::FinnedJellyfish,
+        (_, true) => Technique::SashimiJellyfish,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn x_wing() {
+        //Partway through solving
+        //..9.1..7.....78..4..52.....23......8.....46..4.1.2.5...86...9.........8.9..6.3...
+        let grid = SudokuGrid::from_candidates(
+            "
+            8   4   9   3  1 6  2    7  5
+            6   12  23  5  7 8  13   9  4
+            137 17  5   2  4 9  8    6  13
+            2   3   7   9  6 5  14   14 8
+            5   9   8   1  3 4  6    2  7
+            4   6   1   8  2 7  5    3  9
+            137 8   6   47 5 12 9    14 123
+            137 5   234 47 9 12 1347 8  6
+            9   127 24  6  8 3  147  5  12
+            ",
+        );
+        assert_eq!(
+            grid.find_fish(2),
+            Some(Deduction {
+                technique: Technique::XWing,
+                pattern: Pattern::Fish {
+                    digit: 3,
+                    base: vec![Unit::Row(2), Unit::Row(6)],
+                    cover: vec![Unit::Column(0), Unit::Column(8)],
+                    fins: vec![],
+                },
+                eliminations: vec![Candidate::new(7, 0, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn swordfish() {
+        //Partway through solving
+        //.956....4.8.....7...37..1....95.4..7...9............4.5.481...981....53...7..5...
+        let grid = SudokuGrid::from_candidates(
+            "
+            7   9   5    6 28  1    3  28  4
+            126 8   12   3 4   9    26 7   5
+            26  4   3    7 5   28   1  9   268
+            123 26  9    5 23  4    8  126 7
+            4   7   1268 9 368 2368 26 5   1236
+            23  5   268  1 7   268  9  4   236
+            5   236 4    8 1   36   7  26  9
+            8   1   26   4 9   7    5  3   26
+            9   36  7    2 36  5    4  18  18
+            ",
+        );
+        assert_eq!(
+            grid.find_fish(3),
+            Some(Deduction {
+                technique: Technique::Swordfish,
+                pattern: Pattern::Fish {
+                    digit: 2,
+                    base: vec![Unit::Column(1), Unit::Column(4), Unit::Column(7)],
+                    cover: vec![Unit::Row(0), Unit::Row(3), Unit::Row(6)],
+                    fins: vec![],
+                },
+                eliminations: vec![Candidate::new(3, 0, 2)],
+            })
+        );
+    }
+
+    #[test]
+    fn jellyfish() {
+        //Partway through solving
+        //......7..986....457...98....9.4...6...5.....7.7...9.2....35..8..34......6....2...
+        let grid = SudokuGrid::from_candidates(
+            "
+            5   12 123 26 2346 46 7    9  8
+            9   8  6   7  123  13 23   4  5
+            7   4  23  5  9    8  1236 13 26
+            123 9  12  4  7    5  8    6  13
+            4   6  5   28 28   13 9    13 7
+            13  7  8   16 136  9  5    2  4
+            12  12 7   3  5    46 46   8  9
+            8   3  4   9  16   7  126  5  26
+            6   5  9   18 148  2  134  7  13
+            ",
+        );
+        assert_eq!(
+            grid.find_fish(4),
+            Some(Deduction {
+                technique: Technique::Jellyfish,
+                pattern: Pattern::Fish {
+                    digit: 1,
+                    base: vec![Unit::Row(1), Unit::Row(2), Unit::Row(4), Unit::Row(7)],
+                    cover: vec![
+                        Unit::Column(4),
+                        Unit::Column(5),
+                        Unit::Column(6),
+                        Unit::Column(7),
+                    ],
+                    fins: vec![],
+                },
+                eliminations: vec![
+                    Candidate::new(5, 4, 1),
+                    Candidate::new(8, 4, 1),
+                    Candidate::new(8, 6, 1),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn x_wing_on_rows_and_columns() {
+        //1 can only go in columns 2 and 6 of rows 1 and 5
+        let mut grid = SudokuGrid::new();
+        for col in [0, 2, 3, 4, 6, 7, 8].iter() {
+            grid.remove_candidate(*col, 1);
+            grid.remove_candidate(36 + col, 1);
+        }
+        let mut eliminations = Vec::new();
+        for row in [1, 2, 3, 5, 6, 7, 8].iter() {
+            eliminations.push(Candidate::new(*row, 1, 1));
+            eliminations.push(Candidate::new(*row, 5, 1));
+        }
+        eliminations.sort_by_key(|c| (c.col, c.row));
+        assert_eq!(
+            grid.find_fish(2),
+            Some(Deduction {
+                technique: Technique::XWing,
+                pattern: Pattern::Fish {
+                    digit: 1,
+                    base: vec![Unit::Row(0), Unit::Row(4)],
+                    cover: vec![Unit::Column(1), Unit::Column(5)],
+                    fins: Vec::new(),
+                },
+                eliminations,
+            })
+        );
+
+        //The same, turned on its side
+        let mut grid = SudokuGrid::new();
+        for row in [0, 2, 3, 4, 6, 7, 8].iter() {
+            grid.remove_candidate(row * 9, 1);
+            grid.remove_candidate(row * 9 + 4, 1);
+        }
+        let deduction = grid.find_fish(2).unwrap();
+        assert_eq!(
+            deduction.pattern,
+            Pattern::Fish {
+                digit: 1,
+                base: vec![Unit::Column(0), Unit::Column(4)],
+                cover: vec![Unit::Row(1), Unit::Row(5)],
+                fins: Vec::new(),
+            }
+        );
+        assert_eq!(deduction.eliminations.len(), 14);
+    }
+
+    #[test]
+    fn nothing_without_a_fish() {
+        let grid = SudokuGrid::new();
+        for size in 2..=4 {
+            assert_eq!(grid.find_fish(size), None);
+        }
+    }
+
+    #[test]
+    fn finned_x_wing() {
+        //Partway through solving
+        //.4...8..5.9.5...8.....3......3....51.....18..7......299..1...7..8.....1462...5...
+        let grid = SudokuGrid::from_candidates(
+            "
+            3 4 26 267  1    8    2679 69 5
+            1 9 26 5    2467 2467 267  8  3
+            8 7 5  269  3    269  1    4  26
+            2 6 3  8    79   79   4    5  1
+            4 5 9  236  26   1    8    36 7
+            7 1 8  346  5    346  36   2  9
+            9 3 4  1    8    26   5    7  26
+            5 8 7  2369 69   2369 26   1  4
+            6 2 1  47   47   5    39   39 8
+            ",
+        );
+        assert_eq!(
+            grid.find_finned_fish(2, false),
+            Some(Deduction {
+                technique: Technique::FinnedXWing,
+                pattern: Pattern::Fish {
+                    digit: 2,
+                    base: vec![Unit::Row(2), Unit::Row(6)],
+                    cover: vec![Unit::Column(5), Unit::Column(8)],
+                    fins: vec![(2, 3)],
+                },
+                eliminations: vec![Candidate::new(1, 5, 2)],
+            })
+        );
+    }
+
+    #[test]
+    fn sashimi_x_wing() {
+        //Partway through solving
+        //......51...7..643.8...3..7...4..3.....32659..2..7......61.8.3.7...6.........47...
+        let grid = SudokuGrid::from_candidates(
+            "
+            369  2349 269 49  7  8  5    1     29
+            19   129  7   159 25 6  4    3     8
+            8    14   5   14  3  29 26   7     269
+            1569 159  4   8   19 3  7    256   1256
+            17   178  3   2   6  5  9    48    14
+            2    1589 689 7   19 4  168  568   3
+            4    6    1   59  8  29 3    25    7
+            37   37   289 6   25 1  28   24589 245
+            59   2589 289 3   4  7  1268 2689  126
+            ",
+        );
+        assert_eq!(
+            grid.find_finned_fish(2, true),
+            Some(Deduction {
+                technique: Technique::SashimiXWing,
+                pattern: Pattern::Fish {
+                    digit: 2,
+                    base: vec![Unit::Column(5), Unit::Column(6)],
+                    cover: vec![Unit::Row(2), Unit::Row(6)],
+                    fins: vec![(7, 6), (8, 6)],
+                },
+                eliminations: vec![Candidate::new(6, 7, 2)],
+            })
+        );
+    }
+
+    #[test]
+    fn finned_swordfish() {
+        //Partway through solving
+        //5..1...8.3..7.8.9.......1.4..1.5.9.6..3..9...2.7.1.4.....574....4.........9.....1
+        let grid = SudokuGrid::from_candidates(
+            "
+            5 267 4  1    9    236 2367 8    237
+            3 1   26 7    4    8   26   9    5
+            9 267 8  236  236  5   1    2367 4
+            4 8   1  23   5    7   9    23   6
+            6 5   3  4    28   9   27   1    278
+            2 9   7  368  1    36  4    5    38
+            1 236 26 5    7    4   8    236  9
+            8 4   5  9    236  1   2367 2367 237
+            7 236 9  2368 2368 236 5    4    1
+            ",
+        );
+        assert_eq!(
+            grid.find_finned_fish(3, false),
+            Some(Deduction {
+                technique: Technique::FinnedSwordfish,
+                pattern: Pattern::Fish {
+                    digit: 3,
+                    base: vec![Unit::Row(2), Unit::Row(3), Unit::Row(7)],
+                    cover: vec![Unit::Column(3), Unit::Column(4), Unit::Column(7)],
+                    fins: vec![(7, 6), (7, 8)],
+                },
+                eliminations: vec![Candidate::new(6, 7, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn sashimi_swordfish() {
+        //Partway through solving
+        //..1.....8..7...6..32......1...8.6..4..9..17..1...5....9..4..32....5.7.8...5.6.4..
+        let grid = SudokuGrid::from_candidates(
+            "
+            456 4569 1   39  237 23459 25  347 8
+            45  459  7   1   8   23459 6   34  29
+            3   2    8   6   79  459   59  47  1
+            7   35   23  8   239 6     129 159 4
+            568 568  9   23  4   1     7   56  23
+            1   346  234 7   5   39    8   69  239
+            9   7    6   4   1   8     3   2   5
+            24  134  34  5   239 7     19  8   6
+            28  138  5   239 6   239   4   19  7
+            ",
+        );
+        assert_eq!(
+            grid.find_finned_fish(3, true),
+            Some(Deduction {
+                technique: Technique::SashimiSwordfish,
+                pattern: Pattern::Fish {
+                    digit: 9,
+                    base: vec![Unit::Row(2), Unit::Row(5), Unit::Row(7)],
+                    cover: vec![Unit::Column(4), Unit::Column(5), Unit::Column(6)],
+                    fins: vec![(5, 7), (5, 8)],
+                },
+                eliminations: vec![Candidate::new(3, 6, 9)],
+            })
+        );
+    }
+
+    #[test]
+    fn finned_jellyfish() {
+        //Partway through solving
+        //3......856...7.2...2..58...9......42......7.6.36......5.7....2....98.....6.7..3.4
+        let grid = SudokuGrid::from_candidates(
+            "
+            3   7  149 2    169  169  49  8  5
+            6   58 58  34   7    349  2   1  39
+            14  2  149 13   5    8    469 67 379
+            9   15 15  36   36   7    8   4  2
+            248 48 248 5    19   19   7   3  6
+            7   3  6   8    24   24   19  5  19
+            5   9  7   1346 1346 1346 16  2  8
+            124 14 3   9    8    126  5   67 17
+            128 6  128 7    12   5    3   9  4
+            ",
+        );
+        assert_eq!(
+            grid.find_finned_fish(4, false),
+            Some(Deduction {
+                technique: Technique::FinnedJellyfish,
+                pattern: Pattern::Fish {
+                    digit: 1,
+                    base: vec![Unit::Row(0), Unit::Row(3), Unit::Row(4), Unit::Row(8)],
+                    cover: vec![
+                        Unit::Column(1),
+                        Unit::Column(2),
+                        Unit::Column(4),
+                        Unit::Column(5),
+                    ],
+                    fins: vec![(8, 0)],
+                },
+                eliminations: vec![Candidate::new(7, 1, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn sashimi_jellyfish() {
+        //Partway through solving
+        //..1........5291...48.3....2..3..8.1....14.82.......9...3..6..9..5.43.......9...6.
+        let grid = SudokuGrid::from_candidates(
+            "
+            6 2 1 57  8  4 57 3  9
+            3 7 5 2   9  1 6  4  8
+            4 8 9 3   57 6 1  57 2
+            2 9 3 567 57 8 45 1  46
+            5 6 7 1   4  9 8  2  3
+            8 1 4 567 2  3 9  57 67
+            1 3 2 8   6  5 47 9  47
+            9 5 6 4   3  7 2  8  1
+            7 4 8 9   1  2 3  6  5
+            ",
+        );
+        assert_eq!(
+            grid.find_finned_fish(4, true),
+            Some(Deduction {
+                technique: Technique::SashimiJellyfish,
+                pattern: Pattern::Fish {
+                    digit: 7,
+                    base: vec![
+                        Unit::Column(4),
+                        Unit::Column(6),
+                        Unit::Column(7),
+                        Unit::Column(8),
+                    ],
+                    cover: vec![Unit::Row(0), Unit::Row(2), Unit::Row(5), Unit::Row(6)],
+                    fins: vec![(3, 4)],
+                },
+                eliminations: vec![Candidate::new(5, 3, 7)],
+            })
+        );
+    }
+
+    ///1 can only go in columns 2 and 6 of rows 1 and 5, and in the given column of row 1
+    fn x_wing_with_fin(fin: usize) -> SudokuGrid {
+        let mut grid = SudokuGrid::new();
+        for col in [0, 2, 3, 4, 6, 7, 8].iter().filter(|c| **c != fin) {
+            grid.remove_candidate(*col, 1);
+        }
+        for col in [0, 2, 3, 4, 6, 7, 8].iter() {
+            grid.remove_candidate(36 + col, 1);
+        }
+        return grid;
+    }
+
+    #[test]
+    fn fin_in_the_subgrid_of_a_corner() {
+        //Either the fin r1c3 is 1, or the X-Wing holds and column 2 gets its 1 from row 1 or 5.
+        //Both rule out r2c2 and r3c2.
+        let grid = x_wing_with_fin(2);
+        assert_eq!(grid.find_fish(2), None);
+        assert_eq!(grid.find_finned_fish(2, true), None);
+        assert_eq!(
+            grid.find_finned_fish(2, false),
+            Some(Deduction {
+                technique: Technique::FinnedXWing,
+                pattern: Pattern::Fish {
+                    digit: 1,
+                    base: vec![Unit::Row(0), Unit::Row(4)],
+                    cover: vec![Unit::Column(1), Unit::Column(5)],
+                    fins: vec![(0, 2)],
+                },
+                eliminations: vec![Candidate::new(1, 1, 1), Candidate::new(2, 1, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn fin_outside_the_subgrid_of_every_corner() {
+        //r1c8 sees none of the cover cells outside the base rows, so nothing can be ruled out
+        let grid = x_wing_with_fin(7);
+        assert_eq!(grid.find_fish(2), None);
+        assert_eq!(grid.find_finned_fish(2, false), None);
+        assert_eq!(grid.find_finned_fish(2, true), None);
+    }
+}
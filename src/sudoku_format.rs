@@ -0,0 +1,346 @@
+use super::sudoku_error::SudokuError;
+use super::sudoku_grid::SudokuGrid;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+
+///The text layouts a grid can be read from and written to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridFormat {
+    ///Nine lines of nine comma delimited values, where '*' (or anything that isn't a number)
+    ///represents an empty space
+    Csv,
+    ///A single line of 81 characters, where '.' or '0' represents an empty space
+    Line,
+}
+
+impl GridFormat {
+    ///Works out the format from the first line of a puzzle.
+    ///A row of the comma delimited format always has commas in it, so anything without them is
+    ///treated as a single line puzzle.
+    pub fn detect(first_line: &str) -> GridFormat {
+        return if first_line.contains(',') {
+            GridFormat::Csv
+        } else {
+            GridFormat::Line
+        };
+    }
+}
+
+impl SudokuGrid {
+    ///Reads a grid from a file, in either of the formats described by `GridFormat`
+    pub fn parse_grid(file: File) -> Result<SudokuGrid, SudokuError> {
+        return SudokuGrid::parse_reader(BufReader::new(file));
+    }
+
+    ///Reads a grid from anything that can be read line by line, such as stdin or a network
+    ///buffer. The format is detected from the first line that isn't blank.
+    pub fn parse_reader<R: BufRead>(reader: R) -> Result<SudokuGrid, SudokuError> {
+        let mut lines = Vec::new();
+        for line in reader.lines() {
+            lines.push(line?);
+        }
+
+        let format = match lines.iter().find(|l| !l.trim().is_empty()) {
+            Some(l) => GridFormat::detect(l),
+            None => return Err(SudokuError::TooFewLines { found: 0 }),
+        };
+        return SudokuGrid::parse_lines(&lines, format);
+    }
+
+    ///Reads a grid from a string. The format is detected the same way as for `parse_reader`.
+    pub fn parse_str(input: &str) -> Result<SudokuGrid, SudokuError> {
+        return SudokuGrid::parse_reader(input.as_bytes());
+    }
+
+    ///Reads a grid from a string that is known to be in the given format
+    pub fn parse_str_as(input: &str, format: GridFormat) -> Result<SudokuGrid, SudokuError> {
+        let lines = input.lines().collect::<Vec<&str>>();
+        return SudokuGrid::parse_lines(&lines, format);
+    }
+
+    fn parse_lines<S: AsRef<str>>(
+        lines: &[S],
+        format: GridFormat,
+    ) -> Result<SudokuGrid, SudokuError> {
+        let mut sudoku_grid = SudokuGrid::new();
+
+        match format {
+            GridFormat::Csv => {
+                for (row_num, line) in lines.iter().enumerate() {
+                    if row_num >= 9 {
+                        return Err(SudokuError::TooManyLines { line: row_num });
+                    }
+                    sudoku_grid.parse_csv_row(line.as_ref(), row_num)?;
+                }
+                if lines.len() < 9 {
+                    return Err(SudokuError::TooFewLines { found: lines.len() });
+                }
+            }
+            GridFormat::Line => {
+                //Blank lines around a single line puzzle don't mean anything, so skip them
+                let mut puzzles = lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, l)| !l.as_ref().trim().is_empty());
+                match puzzles.next() {
+                    Some((_, line)) => sudoku_grid.parse_single_line(line.as_ref())?,
+                    None => return Err(SudokuError::TooFewLines { found: 0 }),
+                }
+                if let Some((line_num, _)) = puzzles.next() {
+                    return Err(SudokuError::TooManyLines { line: line_num });
+                }
+            }
+        }
+        return Ok(sudoku_grid);
+    }
+
+    fn parse_csv_row(&mut self, line: &str, row_num: usize) -> Result<(), SudokuError> {
+        let chars = line.split(',').collect::<Vec<&str>>();
+        if chars.len() < 9 {
+            return Err(SudokuError::TooFewColumns {
+                row: row_num,
+                found: chars.len(),
+            });
+        }
+
+        for (col_index, token) in chars.iter().take(9).enumerate() {
+            if let Ok(v) = token.trim().parse::<usize>() {
+                if !(1..=9).contains(&v) {
+                    return Err(SudokuError::ValueOutOfRange {
+                        row: row_num,
+                        col: col_index,
+                        token: token.to_string(),
+                    });
+                }
+
+                //If we have a valid value, then we can indicate that the current
+                //row/col/subgrid contain this value
+                let index = row_num * 9 + col_index;
+                self.check_value(index, v)?;
+                self.place_value(index, v);
+            }
+            //if value is not a number, we'll treat it as blank
+        }
+        return Ok(());
+    }
+
+    fn parse_single_line(&mut self, line: &str) -> Result<(), SudokuError> {
+        let chars = line.trim().chars().collect::<Vec<char>>();
+        if chars.len() != 81 {
+            return Err(SudokuError::WrongLength { found: chars.len() });
+        }
+
+        for (index, c) in chars.iter().enumerate() {
+            match c {
+                '.' | '0' => {}
+                '1'..='9' => {
+                    let v = *c as usize - '0' as usize;
+                    self.check_value(index, v)?;
+                    self.place_value(index, v);
+                }
+                _ => {
+                    return Err(SudokuError::ValueOutOfRange {
+                        row: index / 9,
+                        col: index % 9,
+                        token: c.to_string(),
+                    });
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    ///Writes the grid as nine comma delimited lines, with '*' for empty cells.
+    ///The output can be read back with `parse_str`.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::with_capacity(162);
+        for (i, cell) in self.cells().iter().enumerate() {
+            match cell.value() {
+                Some(x) => output.push_str(&x.to_string()),
+                _ => output.push('*'),
+            }
+            output.push(if i % 9 == 8 { '\n' } else { ',' });
+        }
+        return output;
+    }
+
+    ///Writes the grid as a single line of 81 characters, with '.' for empty cells
+    pub fn to_line(&self) -> String {
+        let mut output = String::with_capacity(81);
+        for cell in self.cells() {
+            match cell.value() {
+                Some(x) => output.push_str(&x.to_string()),
+                _ => output.push('.'),
+            }
+        }
+        return output;
+    }
+
+    ///Writes the grid in the given format
+    pub fn format_as(&self, format: GridFormat) -> String {
+        return match format {
+            GridFormat::Csv => self.to_csv(),
+            GridFormat::Line => self.to_line(),
+        };
+    }
+}
+
+impl FromStr for SudokuGrid {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return SudokuGrid::parse_str(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Read};
+
+    const EXPERT: &str = "*,6,1,*,*,*,8,7,*
+*,*,*,4,5,*,*,*,3
+8,4,*,7,6,*,*,5,*
+*,5,*,*,7,*,3,6,*
+7,*,*,3,8,5,*,*,1
+*,*,4,*,2,*,*,*,*
+*,8,*,*,9,6,*,3,*
+3,*,*,*,4,7,5,*,*
+*,7,2,*,*,*,*,4,*";
+
+    const EXPERT_LINE: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+
+    ///A reader that fails straight away
+    struct Broken;
+
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "broken"));
+        }
+    }
+
+    #[test]
+    fn reads_csv_from_anywhere() {
+        let grid = SudokuGrid::parse_reader(EXPERT.as_bytes()).unwrap();
+        assert_eq!(grid.get_value(0, 1), Some(6));
+        assert_eq!(grid.get_value(8, 7), Some(4));
+        assert_eq!(grid.get_value(0, 0), None);
+
+        let parsed = EXPERT.parse::<SudokuGrid>().unwrap();
+        let file = SudokuGrid::parse_grid(File::open("expert_test.txt").unwrap()).unwrap();
+        assert_eq!(parsed.to_csv(), grid.to_csv());
+        assert_eq!(file.to_csv(), grid.to_csv());
+    }
+
+    #[test]
+    fn entries_are_trimmed_and_anything_else_is_empty() {
+        let padded = EXPERT.replace(',', " , ").replace('*', "x");
+        let grid = SudokuGrid::parse_str(&padded).unwrap();
+        assert_eq!(
+            grid.to_csv(),
+            SudokuGrid::parse_str(EXPERT).unwrap().to_csv()
+        );
+    }
+
+    #[test]
+    fn bad_csv_is_reported() {
+        let rows = EXPERT.lines().collect::<Vec<&str>>();
+        assert!(matches!(
+            SudokuGrid::parse_str(&rows[..8].join("\n")),
+            Err(SudokuError::TooFewLines { found: 8 })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_str(&format!("{}\n{}", EXPERT, rows[0])),
+            Err(SudokuError::TooManyLines { line: 9 })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_str(&EXPERT.replacen("*,6,1,*,", "6,1,*,", 1)),
+            Err(SudokuError::TooFewColumns { row: 0, found: 8 })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_str(&EXPERT.replacen("*,6,1", "*,6,10", 1)),
+            Err(SudokuError::ValueOutOfRange { row: 0, col: 2, .. })
+        ));
+        //A second 6 in the first row
+        assert!(matches!(
+            SudokuGrid::parse_str(&EXPERT.replacen("*,6,1", "6,6,1", 1)),
+            Err(SudokuError::ConflictingValue {
+                row: 0,
+                col: 1,
+                value: 6
+            })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_str("\n\n"),
+            Err(SudokuError::TooFewLines { found: 0 })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_reader(io::BufReader::new(Broken)),
+            Err(SudokuError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn reads_single_lines() {
+        let grid = SudokuGrid::parse_str(EXPERT_LINE).unwrap();
+        assert_eq!(
+            grid.to_csv(),
+            SudokuGrid::parse_str(EXPERT).unwrap().to_csv()
+        );
+
+        //Zeros are empty too, and blank lines around the puzzle are skipped
+        let zeros = format!("\n  {}  \n\n", EXPERT_LINE.replace('.', "0"));
+        assert_eq!(
+            SudokuGrid::parse_str(&zeros).unwrap().to_line(),
+            EXPERT_LINE
+        );
+    }
+
+    #[test]
+    fn bad_lines_are_reported() {
+        assert!(matches!(
+            SudokuGrid::parse_str(&EXPERT_LINE[1..]),
+            Err(SudokuError::WrongLength { found: 80 })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_str(&EXPERT_LINE.replacen('.', "x", 1)),
+            Err(SudokuError::ValueOutOfRange { row: 0, col: 0, .. })
+        ));
+        assert!(matches!(
+            SudokuGrid::parse_str(&format!("{}\n\n{}", EXPERT_LINE, EXPERT_LINE)),
+            Err(SudokuError::TooManyLines { line: 2 })
+        ));
+        //r1c1 given as 6, which r1c2 already is
+        assert!(matches!(
+            SudokuGrid::parse_str(&EXPERT_LINE.replacen('.', "6", 1)),
+            Err(SudokuError::ConflictingValue {
+                row: 0,
+                col: 1,
+                value: 6
+            })
+        ));
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        let grid = SudokuGrid::parse_str(EXPERT).unwrap();
+        assert_eq!(grid.to_line(), EXPERT_LINE);
+        assert_eq!(grid.to_csv().trim_end(), EXPERT);
+        for format in [GridFormat::Csv, GridFormat::Line].iter() {
+            let text = grid.format_as(*format);
+            assert_eq!(GridFormat::detect(text.lines().next().unwrap()), *format);
+            let read = SudokuGrid::parse_str_as(&text, *format).unwrap();
+            assert_eq!(read.to_line(), EXPERT_LINE);
+        }
+
+        let solved = grid.solve_grid().unwrap();
+        let line = solved.to_line();
+        assert!(!line.contains('.'));
+        assert_eq!(
+            SudokuGrid::parse_str(&line).unwrap().to_csv(),
+            solved.to_csv()
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use super::sudoku_deduction::TechniqueSet;
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_solver::{BacktrackingSolver, SudokuSolver};
+
+impl SudokuGrid {
+    ///Creates a random puzzle that has exactly one solution.
+    ///The same seed always gives the same puzzle.
+    ///
+    ///A random complete grid is made first, then its values are taken away one at a time, in a
+    ///random order, putting each one back if taking it away would give the puzzle more than one
+    ///solution.
+    pub fn generate(seed: u64) -> SudokuGrid {
+        let mut random = Random::new(seed);
+        let solver = generating_solver();
+
+        let mut values = random_solution(&mut random, &solver);
+        let mut order = (0..81).collect::<Vec<usize>>();
+        random.shuffle(&mut order);
+
+        for index in order {
+            let removed = values[index].take();
+            if !solver.is_unique(&grid_from_values(&values)) {
+                values[index] = removed;
+            }
+        }
+        return grid_from_values(&values);
+    }
+}
+
+///The three subgrids on the diagonal don't share any rows or columns, so they can be filled in
+///with any order of the digits. Solving from there gives a random complete grid.
+fn random_solution(random: &mut Random, solver: &BacktrackingSolver) -> Vec<Option<usize>> {
+    let mut grid = SudokuGrid::new();
+    for subgrid in [0, 4, 8].iter() {
+        let mut digits = (1..=9).collect::<Vec<usize>>();
+        random.shuffle(&mut digits);
+        for (i, digit) in digits.iter().enumerate() {
+            let row = (subgrid / 3) * 3 + i / 3;
+            let col = (subgrid % 3) * 3 + i % 3;
+            grid.set_value(row, col, *digit)
+                .expect("the diagonal subgrids never conflict");
+        }
+    }
+
+    let solved = solver
+        .solve(&grid)
+        .expect("any filling of the diagonal subgrids can be completed");
+    return solved.cells().iter().map(|c| c.value()).collect();
+}
+
+///Making a puzzle means solving thousands of grids that only need the singles or a few guesses,
+///where looking for the other techniques costs more than the guesses it saves. Leaving them out
+///also keeps the puzzles a seed gives the same whatever techniques the solver learns.
+fn generating_solver() -> BacktrackingSolver {
+    return BacktrackingSolver::new().with_techniques(TechniqueSet::empty());
+}
+
+fn grid_from_values(values: &[Option<usize>]) -> SudokuGrid {
+    let mut grid = SudokuGrid::new();
+    for (index, value) in values.iter().enumerate() {
+        if let Some(v) = value {
+            grid.set_value(index / 9, index % 9, *v)
+                .expect("values are taken from a valid grid");
+        }
+    }
+    return grid;
+}
+
+///A small xorshift random number generator, so generating puzzles doesn't need any dependencies.
+///It's nowhere near good enough for anything that needs real randomness, but plenty for this.
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        //xorshift gets stuck on zero, so mix the seed into a fixed non-zero value
+        Random {
+            state: seed ^ 0x2545_f491_4f6c_dd1d,
+        }
+        .warmed_up()
+    }
+
+    fn warmed_up(mut self) -> Self {
+        if self.state == 0 {
+            self.state = 1;
+        }
+        for _i in 0..8 {
+            self.next();
+        }
+        return self;
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+
+    ///A number from 0 up to, but not including, `n`
+    fn below(&mut self, n: usize) -> usize {
+        return (self.next() % n as u64) as usize;
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_dlx::DancingLinksSolver;
+
+    #[test]
+    fn puzzles_have_one_solution() {
+        for seed in 0..5 {
+            let puzzle = SudokuGrid::generate(seed);
+            assert!(!puzzle.is_solved());
+            assert_eq!(DancingLinksSolver::new().count_solutions(&puzzle, 2), 1);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_puzzle() {
+        assert_eq!(
+            SudokuGrid::generate(42).to_line(),
+            SudokuGrid::generate(42).to_line()
+        );
+        assert_ne!(
+            SudokuGrid::generate(42).to_line(),
+            SudokuGrid::generate(43).to_line()
+        );
+    }
+
+    #[test]
+    fn no_given_can_be_taken_away() {
+        let puzzle = SudokuGrid::generate(7);
+        let values = puzzle
+            .cells()
+            .iter()
+            .map(|c| c.value())
+            .collect::<Vec<Option<usize>>>();
+        for index in (0..81).filter(|i| values[*i].is_some()) {
+            let mut fewer = values.clone();
+            fewer[index] = None;
+            assert!(!generating_solver().is_unique(&grid_from_values(&fewer)));
+        }
+    }
+}
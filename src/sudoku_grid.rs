@@ -1,8 +1,11 @@
 use super::sudoku_cell::SudokuCell;
+use super::sudoku_deduction::{Technique, TechniqueSet};
+use super::sudoku_error::SudokuError;
+use super::sudoku_observer::{SolverEvent, SolverObserver};
+use super::sudoku_solver::{BacktrackingSolver, SudokuSolver};
+use super::sudoku_units::{sees, CELL_UNITS, PEERS, UNITS};
 use super::sudoku_values::SudokuValues;
 use std::fmt;
-use std::fs::File;
-use std::io::BufRead;
 
 #[derive(Clone)]
 pub struct SudokuGrid {
@@ -12,7 +15,14 @@ pub struct SudokuGrid {
     subgrids: Vec<SudokuValues>,
 }
 
+impl Default for SudokuGrid {
+    fn default() -> Self {
+        return SudokuGrid::new();
+    }
+}
+
 impl SudokuGrid {
+    ///Creates a grid where all 81 cells are empty
     pub fn new() -> Self {
         let mut grid = SudokuGrid {
             cells: Vec::with_capacity(81),
@@ -21,290 +31,284 @@ impl SudokuGrid {
             subgrids: Vec::with_capacity(9),
         };
         for _i in 0..9 {
-            grid.rows.push(SudokuValues::new(false));
-            grid.cols.push(SudokuValues::new(false));
-            grid.subgrids.push(SudokuValues::new(false));
+            grid.rows.push(SudokuValues::empty());
+            grid.cols.push(SudokuValues::empty());
+            grid.subgrids.push(SudokuValues::empty());
+        }
+        for i in 0..81 {
+            let row = i / 9;
+            let col = i % 9;
+            grid.cells
+                .push(SudokuCell::new(row, col, SudokuGrid::get_subgrid(row, col)));
         }
 
         return grid;
     }
 
-    pub fn solve_grid(mut self) -> Result<Self, String>{
-        return if self.solve_grid_helper(false, 0) {
-            Ok(self)
-        } else {
-            Err("Unable to solve this puzzle".to_string())
+    ///The cell at the given zero based row and column
+    pub fn cell(&self, row: usize, col: usize) -> &SudokuCell {
+        return &self.cells[row * 9 + col];
+    }
+
+    ///All 81 cells, in reading order
+    pub fn cells(&self) -> &[SudokuCell] {
+        return &self.cells;
+    }
+
+    ///Returns true if two cells, given as zero based (row, column) pairs, share a row, column or
+    ///subgrid. Cells that see each other can never hold the same value. A cell doesn't see
+    ///itself.
+    pub fn sees(first: (usize, usize), second: (usize, usize)) -> bool {
+        return sees(first.0 * 9 + first.1, second.0 * 9 + second.1);
+    }
+
+    ///The value at the given zero based row and column, if there is one
+    pub fn get_value(&self, row: usize, col: usize) -> Option<usize> {
+        return self.cell(row, col).cur_val;
+    }
+
+    ///Enters a value (1 to 9) into an empty cell.
+    ///Fails if the cell already has a value, or the value is already present in the cell's row,
+    ///column or subgrid.
+    pub fn set_value(&mut self, row: usize, col: usize, value: usize) -> Result<(), SudokuError> {
+        if row > 8 || col > 8 {
+            return Err(SudokuError::InvalidPosition { row, col });
+        }
+        if !(1..=9).contains(&value) {
+            return Err(SudokuError::ValueOutOfRange {
+                row,
+                col,
+                token: value.to_string(),
+            });
+        }
+        let index = row * 9 + col;
+        if self.cells[index].cur_val.is_some() {
+            return Err(SudokuError::CellOccupied { row, col });
         }
+        self.check_value(index, value)?;
+
+        self.place_value(index, value);
+        return Ok(());
+    }
+
+    ///Returns true if every cell has a value
+    pub fn is_solved(&self) -> bool {
+        return self.get_next_unsolved_cell_index().is_none();
     }
 
     /// This is supposed to be the 'main' function for filling out the rest of the grid
     /// There are basically three approaches we use for solving the grid.
     ///
-    /// The first is to check each cell's list of possible values, and compare it against the
-    /// existing values already entered in that cell's row, column, or subgrid.
+    /// The first is to check each cell's list of possible values, which has every value already
+    /// entered in that cell's row, column, or subgrid taken out of it. If only one is left, that
+    /// is the cell's value.
     ///
     /// The second is more involved. It takes a cell, and compares it against other unsolved cells
     /// within the same row, column, or subgrid. If a given cell has a possible value that is
-    /// impossible in any other cells, then that is the only possible value for that cell
+    /// impossible in the other cells of one of those, then that is the only possible value for
+    /// that cell
+    ///
+    /// Both of these are done by 'deduce'. A `BacktrackingSolver` given more `Technique`s, such
+    /// as naked and hidden pairs, also looks for their patterns when those two get stuck, ruling
+    /// possible values out of cells until one of the first two approaches can make progress
+    /// again. This doesn't, since guessing is quicker.
     ///
     /// The third is a brute force approach only used when the first two approaches fail to make any
-    /// progress. We clone the grid, find an unsolved cell, set its value to one of its
-    /// possibilities, then call 'solve_grid_helper' on that cloned grid. If that value is incorrect,
-    /// then 'solve_grid_helper' will return false
-
-    pub fn solve_grid_helper(&mut self, is_guess: bool, clone_num: usize) -> bool {
-        let mut complete: bool = false;
-        let mut iterations: usize = 0;
-
-        //
-        let mut some_change: bool = true;
-
-        while !complete && iterations <= 100 {
-            complete = true;
-            while some_change {
-                some_change = false;
-                for i in 0..81 {
-                    if let None = self.cells[i].cur_val {
-                        let row = self.cells[i].row;
-                        let col = self.cells[i].col;
-                        let subgrid = self.cells[i].subgrid;
-
-                        match SudokuGrid::calc_possibilities_in_cell(
-                            &mut self.cells[i],
-                            &mut self.rows[row],
-                            &mut self.cols[col],
-                            &mut self.subgrids[subgrid],
-                        ) {
-                            Possibilities::One => {
-                                // println!(
-                                //     "Found a value! {:?}, clone# {}",
-                                //     self.cells[i], clone_num
-                                // );
-                                some_change = true;
-                            }
-                            Possibilities::Many => {
-                                //println!("Cell# {:?} clone# {}", i, clone_num);
-                                complete = false;
-                            }
-                            Possibilities::None => {
-                                // println!("Failure! {:?}, clone# {}", self.cells[i], clone_num);
-                                return false;
-                            }
-                        };
-
-                        //println!("{:?}", self.cells[i]);
-                    }
-                }
+    /// progress. We pick an unsolved cell and try each of its possibilities in turn, going back to
+    /// the next possibility whenever a guess leads to a cell with no possible values. This is
+    /// done by the 'Solutions' iterator, which keeps the guesses still to be tried on a stack.
+    ///
+    /// Every guess fills in one more cell, so the search always finishes: either with a
+    /// solution, or having tried every possibility, which proves there isn't one.
+    /// Returns `SudokuError::Contradiction` if the given values already rule out every value for
+    /// some cell, and `SudokuError::SearchExhausted` if every guess led to a contradiction.
+    ///
+    /// Which cell gets guessed is up to the `BranchStrategy`. This uses the default one, a
+    /// `BacktrackingSolver` can be used to pick another, or to follow along with a
+    /// `SolverObserver`.
+    pub fn solve_grid(self) -> Result<Self, SudokuError> {
+        return BacktrackingSolver::new().solve(&self);
+    }
+
+    ///Counts the solutions of the grid, giving up once `limit` of them have been found.
+    ///Unlike `solve_grid`, the search carries on after the first solution, so this can tell a
+    ///proper puzzle (exactly one solution) apart from one with many.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        return BacktrackingSolver::new().count_solutions(self, limit);
+    }
+
+    ///Returns true if the grid has exactly one solution
+    pub fn is_unique(&self) -> bool {
+        return self.count_solutions(2) == 1;
+    }
+
+    ///Applies the first two approaches described on 'solve_grid', and the given techniques
+    ///whenever those get stuck, until none of them can find anything else. The observer is told
+    ///about everything they find.
+    ///Returns true if that completes the grid, or an error if some cell is left with no possible
+    ///values.
+    pub(crate) fn deduce(
+        &mut self,
+        techniques: TechniqueSet,
+        observer: &mut dyn SolverObserver,
+    ) -> Result<bool, SudokuError> {
+        let result = self.deduce_steps(techniques, observer);
+        if let Err(SudokuError::Contradiction { row, col }) = result {
+            observer.on_event(&SolverEvent::Contradiction { row, col });
+        }
+        return result;
+    }
+
+    fn deduce_steps(
+        &mut self,
+        techniques: TechniqueSet,
+        observer: &mut dyn SolverObserver,
+    ) -> Result<bool, SudokuError> {
+        loop {
+            if self.deduce_singles(observer)? {
+                return Ok(true);
+            }
+            match self.find_deduction(techniques) {
+                Some(deduction) => self.apply_deduction(&deduction, observer),
+                None => return Ok(false),
             }
+        }
+    }
 
-            for i in 0..81 {
-                if let None = self.cells[i].cur_val {
-                    match self.compare_cell_against_other_cells(i) {
-                        Some(v) => {
-                            let cur_row = self.cells[i].row;
-                            let cur_col = self.cells[i].col;
-                            let cur_sub = self.cells[i].subgrid;
-
-                            self.cells[i].cur_val = Some(v);
-                            self.rows[cur_row][v - 1] = true;
-                            self.cols[cur_col][v - 1] = true;
-                            self.subgrids[cur_sub][v - 1] = true;
-
-                            some_change = true;
-                            // println!("Found a value! {:?}, clone# {}", self.cells[i], clone_num);
-
-                            break;
-                        }
-                        _ => {
-                            complete = false;
-                            continue;
-                        }
-                    }
+    fn deduce_singles(&mut self, observer: &mut dyn SolverObserver) -> Result<bool, SudokuError> {
+        //Cells that might be down to one possible value. Every cell is checked once, after that
+        //only the peers of a newly placed value can have changed.
+        let mut pending: Vec<usize> = (0..81).rev().collect();
+
+        loop {
+            while let Some(i) = pending.pop() {
+                if self.cells[i].cur_val.is_some() {
+                    continue;
                 }
+                match SudokuGrid::calc_possibilities_in_cell(&self.cells[i]) {
+                    Possibilities::One(v) => {
+                        self.place_deduced_value(i, v, Technique::NakedSingle, observer);
+                        pending.extend_from_slice(&PEERS[i]);
+                    }
+                    Possibilities::Many => {}
+                    Possibilities::None => {
+                        return Err(SudokuError::Contradiction {
+                            row: self.cells[i].row,
+                            col: self.cells[i].col,
+                        });
+                    }
+                };
             }
 
-            //if the deductive approaches fail to make any progress,
-            //we need to brute force this and just try guessing things
-            if !some_change && !complete {
-                //we don't want to override our current grid just yet.
-                let mut clone_grid = Box::new(self.clone());
-                match clone_grid.get_next_unsolved_cell_index() {
-                    Some(s) => {
-                        for i in 0..9 {
-                            if clone_grid.cells[s].possible_vals[i] {
-                                let cur_row = clone_grid.cells[s].row;
-                                let cur_col = clone_grid.cells[s].col;
-                                let cur_sub = clone_grid.cells[s].subgrid;
-
-                                clone_grid.rows[cur_row][i] = true;
-                                clone_grid.cols[cur_col][i] = true;
-                                clone_grid.subgrids[cur_sub][i] = true;
-
-                                clone_grid.cells[s].cur_val = Some(i + 1);
-                                // println!(
-                                //     "Guessing {} for {:?}, clone#: {}\n{:?}",
-                                //     (i + 1),
-                                //     clone_grid.cells[s],
-                                //     clone_num,
-                                //     clone_grid
-                                // );
-
-                                if clone_grid.solve_grid_helper(true, clone_num + 1) {
-                                    self.copy_over_grid(&clone_grid);
-                                    break;
-                                } else {
-                                    // println!(
-                                    //     "Guess {} for {:?} was not correct",
-                                    //     i + 1,
-                                    //     clone_grid.cells[s],
-                                    // );
-
-                                    self.cells[s].possible_vals[i] = false;
-                                    clone_grid.copy_over_grid(&self);
-                                }
-                            }
-                        }
+            let mut some_change: bool = false;
+            for (i, peers) in PEERS.iter().enumerate() {
+                if self.cells[i].cur_val.is_none() {
+                    if let Some(v) = self.compare_cell_against_other_cells(i)? {
+                        self.place_deduced_value(i, v, Technique::HiddenSingle, observer);
+                        pending.extend_from_slice(peers);
+                        some_change = true;
                     }
-                    _ => {}
                 }
             }
-            iterations += 1;
-        }
-        return if complete {
-            if !is_guess {
-                println!("Solved!");
-                println!("{:?}", self);
-            }
-            true
-        } else {
-            if !is_guess {
-                println!("I can't solve this!");
-                println!("{:?}", self);
+
+            if !some_change {
+                return Ok(self.is_solved());
             }
-            false
-        };
+        }
+    }
+
+    fn place_deduced_value(
+        &mut self,
+        index: usize,
+        value: usize,
+        technique: Technique,
+        observer: &mut dyn SolverObserver,
+    ) {
+        observer.on_event(&SolverEvent::Placement {
+            row: self.cells[index].row,
+            col: self.cells[index].col,
+            value,
+            technique,
+        });
+        self.place_value_observed(index, value, observer);
     }
 
     ///Every sudoku cell, if it does not already contain a value,
-    ///will have a list of potential values, represented by
-    ///an array of booleans. On the grid itself, we have arrays of arrays
-    ///holding what values are already present for each row, column, or subgrid.
-    ///We pass in the cell's list of 'potential' values, then look at the
-    ///values present in row/column/subgrid. If a value is present in any of those
-    ///groupings, that value is no longer possible within the cell.
-    ///Returns true if a cell value is solved
-    fn calc_possibilities_in_cell(
-        cell: &mut SudokuCell,
-        row: &mut SudokuValues,
-        col: &mut SudokuValues,
-        subgrid: &mut SudokuValues,
-    ) -> Possibilities {
-        for i in 0..9 {
-            //A value can only be possible for the cell if it already exists as a possible value,
-            //and it's not present in the cell's row, column or subgrid
-            cell.possible_vals[i] = cell.possible_vals[i] && !row[i];
-            cell.possible_vals[i] = cell.possible_vals[i] && !col[i];
-            cell.possible_vals[i] = cell.possible_vals[i] && !subgrid[i];
-        }
-        let mut count = 0;
-        let mut new_val_index = 0;
-
-        //Check how values are possible for the cell.
-        for i in 0..9 {
-            if cell.possible_vals[i] {
-                count += 1;
-                new_val_index = i;
-            }
-            //If there is more than one, it means this cell's value is still uncertain
-            if count > 1 {
-                return Possibilities::Many;
-            }
+    ///will have a set of potential values. Whenever a value is placed, it is
+    ///taken out of the potential values of the cell's peers (every other cell in the
+    ///same row, column, or subgrid), so the set is always up to date.
+    ///All that is left is to look at how many values are still possible.
+    fn calc_possibilities_in_cell(cell: &SudokuCell) -> Possibilities {
+        //If there is more than one, it means this cell's value is still uncertain
+        if cell.possible_vals.len() > 1 {
+            return Possibilities::Many;
         }
 
         //If we are returning this, it probably means one of the values of another cell is wrong.
-        if count == 0 {
-            return Possibilities::None;
-        }
-
-        //If there is only possibility, set the cell's value to that, and mark it on the cell's row,
-        // column, and subgrid
-        cell.cur_val = Some(new_val_index + 1);
-        row[new_val_index] = true;
-        col[new_val_index] = true;
-        subgrid[new_val_index] = true;
-        return Possibilities::One;
+        return match cell.possible_vals.single() {
+            Some(v) => Possibilities::One(v),
+            None => Possibilities::None,
+        };
     }
 
     ///calc_possibilities_in_cell can solve many puzzles on its own,
     ///but in some cases many cells will be left with multiple possibilities.
     ///In that case, we want look at each cell, and then compare it to
-    ///every other cell in that cell's row/column/subgrid.
+    ///the other cells in that cell's row, column and subgrid.
     ///If we find a possibility in a cell that is not possible in any other
-    ///cell in the row/column/subgrid, then we return that value
-    fn compare_cell_against_other_cells(&self, cell_index: usize) -> Option<usize> {
+    ///cell in the row, column or subgrid, then we return that value.
+    ///If there are two such values, the cell can't hold both, so the grid has no solution.
+    fn compare_cell_against_other_cells(
+        &self,
+        cell_index: usize,
+    ) -> Result<Option<usize>, SudokuError> {
         //The cell we are checking
         let main_cell = &self.cells[cell_index];
-        // println!("{:?}", main_cell);
-        let cur_row = main_cell.row;
-        let cur_col = main_cell.col;
-        let cur_sub = main_cell.subgrid;
 
-        //A counter for each potential value we discover in the other cells.
-        let mut val_counts = [0; 9];
-
-        for j in 0..81 {
-            if cell_index == j {
-                continue;
-            }
-
-            //The cell we are comparing against
-            let cmp_cell = &self.cells[j];
-
-            if cmp_cell.row == cur_row {
-                for k in 0..9 {
-                    if cmp_cell.possible_vals[k] {
-                        val_counts[k] += 1;
-                    }
+        for unit in CELL_UNITS[cell_index].iter() {
+            //Every potential value we discover in the other cells of the unit,
+            //along with the values the unit already has
+            let mut other_vals = SudokuValues::empty();
+            for j in UNITS[*unit].iter() {
+                if *j == cell_index {
+                    continue;
                 }
-            } else if cmp_cell.col == cur_col {
-                for k in 0..9 {
-                    if cmp_cell.possible_vals[k] {
-                        val_counts[k] += 1;
-                    }
-                }
-            } else if cmp_cell.subgrid == cur_sub {
-                for k in 0..9 {
-                    if cmp_cell.possible_vals[k] {
-                        val_counts[k] += 1;
-                    }
+
+                //The cell we are comparing against
+                let cmp_cell = &self.cells[*j];
+                match cmp_cell.cur_val {
+                    Some(v) => other_vals.insert(v),
+                    None => other_vals = other_vals.union(cmp_cell.possible_vals),
                 }
-            } else {
-                continue;
             }
-        }
 
-        //Check val_counts. If a value is listed as a possible value for the cell, and it isn't
-        //present as a possible value in any cell within the same row/col/subgrid,
-        //
-        for v in 0..9 {
-            if main_cell.possible_vals[v] {
-                if val_counts[v] == 0 {
-                    // The 'real' value is always the index + 1
-                    // A hash map might have been clearer here, but since a sudoku grid has fixed
-                    // dimensions, we can get away with something more flexible and efficient
-                    return Some(v + 1);
+            let only_here = other_vals.complement();
+            if only_here.len() > 1 {
+                return Err(SudokuError::Contradiction {
+                    row: main_cell.row,
+                    col: main_cell.col,
+                });
+            }
+            if let Some(v) = only_here.single() {
+                if !main_cell.possible_vals.contains(v) {
+                    //Nowhere in the unit can hold this value
+                    return Err(SudokuError::Contradiction {
+                        row: main_cell.row,
+                        col: main_cell.col,
+                    });
                 }
+                return Ok(Some(v));
             }
         }
-        return None;
+        return Ok(None);
     }
 
     ///Find the index of the next unsolved cell in the grid.
     /// If we return 'None', it basically means the puzzle is solved
-    fn get_next_unsolved_cell_index(&self) -> Option<usize> {
+    pub(crate) fn get_next_unsolved_cell_index(&self) -> Option<usize> {
         let mut index = 0;
         while index < 81 {
-            if let None = self.cells[index].cur_val {
+            if self.cells[index].cur_val.is_none() {
                 return Some(index);
             }
             index += 1;
@@ -312,107 +316,68 @@ impl SudokuGrid {
         return None;
     }
 
-    pub fn parse_grid(file: File) -> Result<SudokuGrid, String> {
-        let reader = std::io::BufReader::new(file);
-        let mut sudoku_grid = SudokuGrid::new();
-        let mut row_num: usize = 0;
-
-        for line in reader.lines() {
-            match line {
-                Ok(li) if row_num < 9 => {
-                    match sudoku_grid.parse_line(&li, row_num) {
-                        Ok(_) => row_num += 1,
-                        Err(s) => return Err(s)
-                    };
-                }
-                Ok(_) => {
-                    return Err("Too many lines".to_string());
-                }
-                Err(err) => return Err(err.to_string())
-            }
+    ///Makes sure a value isn't already present in the cell's row, column or subgrid
+    pub(crate) fn check_value(&self, index: usize, value: usize) -> Result<(), SudokuError> {
+        let cell = &self.cells[index];
+        if self.rows[cell.row].contains(value)
+            || self.cols[cell.col].contains(value)
+            || self.subgrids[cell.subgrid].contains(value)
+        {
+            return Err(SudokuError::ConflictingValue {
+                row: cell.row,
+                col: cell.col,
+                value,
+            });
         }
-        return Ok(sudoku_grid);
+        return Ok(());
     }
 
-    fn parse_line(&mut self, line: &str, row_num: usize) -> Result<(), String> {
-        let chars = line.split(',').collect::<Vec<&str>>();
-        if chars.len() < 9 {
-            return Err("All lines must have 9 characters".to_string());
+    ///Sets a cell's value, marks it as present on the cell's row, column and subgrid,
+    ///and takes it out of the possible values of the cell's peers
+    pub(crate) fn place_value(&mut self, index: usize, value: usize) {
+        let cur_row = self.cells[index].row;
+        let cur_col = self.cells[index].col;
+        let cur_sub = self.cells[index].subgrid;
+
+        self.cells[index].cur_val = Some(value);
+        self.cells[index].possible_vals = SudokuValues::empty();
+        self.rows[cur_row].insert(value);
+        self.cols[cur_col].insert(value);
+        self.subgrids[cur_sub].insert(value);
+
+        for peer in PEERS[index].iter() {
+            self.cells[*peer].possible_vals.remove(value);
         }
+    }
 
-        for col_index in 0..9 {
-            let new_cell: SudokuCell;
-            let subgrid_index = SudokuGrid::get_subgrid(row_num, col_index as usize);
-
-            if let Ok(v) = chars[col_index].parse::<usize>() {
-                if v < 1 || v > 9 {
-                    return Err("Values must be numbers between 1 and 9.".to_string());
-                }
-
-                let value = Some(v);
-                new_cell = SudokuCell {
-                    cur_val: value,
-                    row: row_num,
-                    col: col_index as usize,
-                    subgrid: subgrid_index,
-                    possible_vals: SudokuValues { values: [false; 9] },
-                };
-                //If we have a valid value, then we can indicate that the current
-                //row/col/subgrid contain this value
-                self.rows[row_num][v - 1] = true;
-                self.cols[col_index][v - 1] = true;
-                self.subgrids[subgrid_index][v - 1] = true;
-            } else {
-                //if value is not a number, we'll treat it as blank
-                new_cell = SudokuCell::new(row_num, col_index, subgrid_index);
+    ///Places a value the same way as 'place_value', telling the observer about every possible
+    ///value of the peers that it rules out
+    pub(crate) fn place_value_observed(
+        &mut self,
+        index: usize,
+        value: usize,
+        observer: &mut dyn SolverObserver,
+    ) {
+        for peer in PEERS[index].iter() {
+            let cell = &self.cells[*peer];
+            if cell.cur_val.is_none() && cell.possible_vals.contains(value) {
+                observer.on_event(&SolverEvent::Elimination {
+                    row: cell.row,
+                    col: cell.col,
+                    value,
+                });
             }
-            self.cells.push(new_cell);
         }
-        return Ok(());
+        self.place_value(index, value);
     }
 
-    fn get_subgrid(row: usize, col: usize) -> usize {
-        return if row < 3 {
-            if col < 3 {
-                0
-            } else if col < 6 {
-                1
-            } else {
-                2
-            }
-        } else if row < 6 {
-            if col < 3 {
-                3
-            } else if col < 6 {
-                4
-            } else {
-                5
-            }
-        } else {
-            if col < 3 {
-                6
-            } else if col < 6 {
-                7
-            } else {
-                8
-            }
-        };
+    ///Takes a value out of a cell's possible values, without placing anything
+    pub(crate) fn remove_candidate(&mut self, index: usize, value: usize) {
+        self.cells[index].possible_vals.remove(value);
     }
 
-    fn copy_over_grid(&mut self, grid: &SudokuGrid) {
-        for i in 0..81 {
-            self.cells[i].cur_val = grid.cells[i].cur_val;
-            for j in 0..9 {
-                self.cells[i].possible_vals[j] = grid.cells[i].possible_vals[j];
-            }
-        }
-        for i in 0..9 {
-            for j in 0..9 {
-                self.rows[i][j] = grid.rows[i][j];
-                self.cols[i][j] = grid.cols[i][j];
-                self.subgrids[i][j] = grid.subgrids[i][j];
-            }
-        }
+    fn get_subgrid(row: usize, col: usize) -> usize {
+        return (row / 3) * 3 + col / 3;
     }
 }
 
@@ -437,7 +402,174 @@ impl fmt::Debug for SudokuGrid {
 }
 
 enum Possibilities {
-    One,
+    One(usize),
     Many,
     None,
 }
+
+#[cfg(test)]
+impl SudokuGrid {
+    ///A grid written out cell by cell, for testing the techniques on. Cells are separated by
+    ///whitespace: a single digit is a placed value, `.` an empty cell that can still be anything,
+    ///and several digits an empty cell that can only be those.
+    pub(crate) fn from_candidates(text: &str) -> SudokuGrid {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        assert_eq!(tokens.len(), 81, "a grid needs 81 cells");
+        let mut grid = SudokuGrid::new();
+        for (i, token) in tokens.iter().enumerate() {
+            if token.len() == 1 && *token != "." {
+                grid.place_value(i, token.parse().unwrap());
+            }
+        }
+        for (i, token) in tokens.iter().enumerate() {
+            if token.len() > 1 {
+                let mut candidates = SudokuValues::empty();
+                for digit in token.chars() {
+                    candidates.insert(digit.to_digit(10).unwrap() as usize);
+                }
+                let cell = &mut grid.cells[i];
+                cell.possible_vals = cell.possible_vals.intersection(candidates);
+            }
+        }
+        return grid;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_stats::SolveStats;
+    use std::fs::File;
+
+    const ONE_SOLUTION: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    //The first puzzle with a few givens taken out, which leaves it with three solutions
+    const SEVERAL_SOLUTIONS: &str =
+        ".61...8......5...384.76..5..5..7.36.7..3.5..1..4.2.....8..96.3.3...475...72......";
+    const HARD: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+    //r1c1 given as 2, which leaves the puzzle without a solution
+    const NO_SOLUTION: &str =
+        "261...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+
+    fn grid(line: &str) -> SudokuGrid {
+        return SudokuGrid::parse_str(line).unwrap();
+    }
+
+    #[test]
+    fn placing_a_value_updates_its_peers() {
+        let mut grid = SudokuGrid::new();
+        grid.place_value(40, 7);
+        assert_eq!(grid.get_value(4, 4), Some(7));
+        assert!(grid.cell(4, 4).candidates().is_empty());
+        for i in 0..81 {
+            let cell = &grid.cells()[i];
+            assert_eq!(
+                cell.candidates().contains(7),
+                i != 40 && !PEERS[40].contains(&i)
+            );
+            if i != 40 {
+                assert_eq!(cell.candidates().len(), 9 - PEERS[40].contains(&i) as usize);
+            }
+        }
+        assert!(
+            grid.rows[4].contains(7) && grid.cols[4].contains(7) && grid.subgrids[4].contains(7)
+        );
+        assert!(matches!(
+            grid.check_value(44, 7),
+            Err(SudokuError::ConflictingValue {
+                row: 4,
+                col: 8,
+                value: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn singles_are_told_apart() {
+        //Row 1 has every digit but 9
+        let mut grid = SudokuGrid::parse_str(&format!("12345678.{}", ".".repeat(72))).unwrap();
+        let mut stats = SolveStats::new();
+        assert!(!grid.deduce(TechniqueSet::singles(), &mut stats).unwrap());
+        assert_eq!(grid.get_value(0, 8), Some(9));
+        assert_eq!(stats.placements(Technique::NakedSingle), 1);
+        assert_eq!(stats.total_placements(), 1);
+
+        //The 5s in the rest of the grid leave r1c9 as the only place for 5 in row 1, while it
+        //could still be anything
+        let mut grid = SudokuGrid::from_candidates(
+            "
+            . . . . . . . . .
+            5 . . . . . . . .
+            . . . . 5 . . . .
+            . . . . . . . . .
+            . . . . . . 5 . .
+            . . . . . . . . .
+            . . . . . . . . .
+            . . . . . . . . .
+            . . . . . . . 5 .
+            ",
+        );
+        let mut stats = SolveStats::new();
+        assert!(!grid.deduce(TechniqueSet::singles(), &mut stats).unwrap());
+        assert_eq!(grid.get_value(0, 8), Some(5));
+        assert_eq!(stats.placements(Technique::HiddenSingle), 1);
+        assert_eq!(stats.total_placements(), 1);
+    }
+
+    #[test]
+    fn solves_hard_puzzles() {
+        for name in ["expert_test.txt", "hard_test.txt"].iter() {
+            let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), name);
+            let puzzle = SudokuGrid::parse_grid(File::open(path).unwrap()).unwrap();
+            let solution = puzzle.clone().solve_grid().unwrap();
+            assert!(solution.is_solved());
+            for (given, value) in puzzle.to_line().bytes().zip(solution.to_line().bytes()) {
+                assert!(given == b'.' || given == value);
+            }
+            assert_eq!(solution.count_solutions(2), 1);
+        }
+        assert_eq!(
+            grid(HARD).solve_grid().unwrap().to_line(),
+            "812753649943682175675491283154237896369845721287169534521974368438526917796318452"
+        );
+    }
+
+    #[test]
+    fn proves_there_is_no_solution() {
+        //The hard puzzle with r1c2 given as 2. The singles don't find anything wrong, so only
+        //trying every guess shows there is no solution.
+        let exhausted =
+            "82.........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+        assert!(matches!(
+            grid(exhausted).solve_grid(),
+            Err(SudokuError::SearchExhausted)
+        ));
+        assert!(grid(NO_SOLUTION).solve_grid().unwrap_err().is_unsolvable());
+        //Row 1 holds 1 to 8, and column 9 already has a 9, so r1c9 has nothing left
+        let clash =
+            "12345678.........9...............................................................";
+        assert!(matches!(
+            grid(clash).solve_grid(),
+            Err(SudokuError::Contradiction { row: 0, col: 8 })
+        ));
+    }
+
+    #[test]
+    fn counts_up_to_the_limit() {
+        assert_eq!(grid(ONE_SOLUTION).count_solutions(10), 1);
+        assert_eq!(grid(SEVERAL_SOLUTIONS).count_solutions(10), 3);
+        assert_eq!(grid(SEVERAL_SOLUTIONS).count_solutions(2), 2);
+        assert_eq!(grid(SEVERAL_SOLUTIONS).count_solutions(0), 0);
+        assert_eq!(grid(NO_SOLUTION).count_solutions(10), 0);
+        assert_eq!(SudokuGrid::new().count_solutions(100), 100);
+    }
+
+    #[test]
+    fn only_proper_puzzles_are_unique() {
+        assert!(grid(ONE_SOLUTION).is_unique());
+        assert!(!grid(SEVERAL_SOLUTIONS).is_unique());
+        assert!(!grid(NO_SOLUTION).is_unique());
+        assert!(!SudokuGrid::new().is_unique());
+    }
+}
@@ -0,0 +1,134 @@
+use super::sudoku_deduction::{Candidate, Deduction, Pattern, Technique, Unit};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_units::{CELL_UNITS, SUBGRID_UNIT, UNITS};
+use std::ops::Range;
+
+impl SudokuGrid {
+    ///Pointing: a digit that can only go in one row or column of a subgrid has to go in that
+    ///part of the line, so it can't go anywhere else on the line.
+    pub(crate) fn find_pointing(&self) -> Option<Deduction> {
+        return self.find_locked_candidates(SUBGRID_UNIT..27, Technique::Pointing);
+    }
+
+    ///Box/line reduction: a digit that can only go in one subgrid's part of a row or column has
+    ///to go there, so it can't go anywhere else in the subgrid.
+    pub(crate) fn find_box_line_reduction(&self) -> Option<Deduction> {
+        return self.find_locked_candidates(0..SUBGRID_UNIT, Technique::BoxLineReduction);
+    }
+
+    ///Looks for a digit whose places in one of the `from` units all lie in one other unit, and
+    ///rules it out of the rest of that unit
+    fn find_locked_candidates(
+        &self,
+        from: Range<usize>,
+        technique: Technique,
+    ) -> Option<Deduction> {
+        for unit in from {
+            for digit in 1..=9 {
+                let places = self.places_in(unit, digit);
+                //One place would be a hidden single
+                if places.len() < 2 {
+                    continue;
+                }
+
+                //Every unit the places share has to be one the first place is in
+                for other in CELL_UNITS[places[0]].iter().cloned() {
+                    if other == unit || !places.iter().all(|p| CELL_UNITS[*p].contains(&other)) {
+                        continue;
+                    }
+                    let eliminations = UNITS[other]
+                        .iter()
+                        .filter(|i| !UNITS[unit].contains(i))
+                        .filter(|i| self.candidates_at(**i).contains(digit))
+                        .map(|i| Candidate::at(*i, digit))
+                        .collect::<Vec<Candidate>>();
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique,
+                            pattern: Pattern::LockedCandidates {
+                                digit,
+                                from: Unit::from_index(unit),
+                                to: Unit::from_index(other),
+                            },
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointing() {
+        //Partway through solving
+        //.3........2.1...69..67..1..7....23....4.958....8.........94372....2........68..1.
+        let grid = SudokuGrid::from_candidates(
+            "
+            1   3   9 5 6 4 2  8 7
+            45  2   7 1 3 8 45 6 9
+            458 458 6 7 2 9 1  3 45
+            7   9   5 8 1 2 3  4 6
+            2   6   4 3 9 5 8  7 1
+            3   1   8 4 7 6 9  5 2
+            6   58  1 9 4 3 7  2 58
+            48  7   3 2 5 1 6  9 48
+            9   45  2 6 8 7 45 1 3
+            ",
+        );
+        assert_eq!(
+            grid.find_pointing(),
+            Some(Deduction {
+                technique: Technique::Pointing,
+                pattern: Pattern::LockedCandidates {
+                    digit: 5,
+                    from: Unit::Subgrid(6),
+                    to: Unit::Column(1),
+                },
+                eliminations: vec![Candidate::new(2, 1, 5)],
+            })
+        );
+    }
+
+    #[test]
+    fn box_line_reduction() {
+        //Partway through solving
+        //.12.6....7........456..7...5...2.31....7.......3..6.8..6...29..1.7....4..4.3..2.7
+        let grid = SudokuGrid::from_candidates(
+            "
+            8 1 2 59 6    3  45 7 459
+            7 3 9 58 1458 14 6  2 58
+            4 5 6 2  89   7  1  3 89
+            5 7 4 89 2    89 3  1 6
+            6 8 1 7  3    45 45 9 2
+            2 9 3 1  45   6  7  8 45
+            3 6 8 4  7    2  9  5 1
+            1 2 7 6  59   59 8  4 3
+            9 4 5 3  18   18 2  6 7
+            ",
+        );
+        assert_eq!(
+            grid.find_box_line_reduction(),
+            Some(Deduction {
+                technique: Technique::BoxLineReduction,
+                pattern: Pattern::LockedCandidates {
+                    digit: 5,
+                    from: Unit::Column(3),
+                    to: Unit::Subgrid(1),
+                },
+                eliminations: vec![Candidate::new(1, 4, 5)],
+            })
+        );
+    }
+
+    #[test]
+    fn nothing_when_no_digit_is_locked() {
+        assert_eq!(SudokuGrid::new().find_pointing(), None);
+        assert_eq!(SudokuGrid::new().find_box_line_reduction(), None);
+    }
+}
@@ -0,0 +1,170 @@
+use super::sudoku_deduction::{Deduction, Technique};
+
+//Rows and columns in events are zero based, the same as the rest of the API.
+
+///Something the solver did, reported as it happens
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolverEvent {
+    ///Deduction placed a value in a cell
+    Placement {
+        row: usize,
+        col: usize,
+        value: usize,
+        technique: Technique,
+    },
+    ///A technique other than the singles found a step of logic. An `Elimination` follows for
+    ///each value it rules out.
+    Deduction(Deduction),
+    ///A value was taken out of a cell's possible values
+    Elimination {
+        row: usize,
+        col: usize,
+        value: usize,
+    },
+    ///Deduction got stuck, so a value is being guessed.
+    ///`depth` counts the guesses that lead to this one, including itself.
+    Guess {
+        row: usize,
+        col: usize,
+        value: usize,
+        depth: usize,
+    },
+    ///A copy of the grid was made to hold a guess at `depth`, to be tried once the ones before
+    ///it have been
+    GridCloned { depth: usize },
+    ///The guess at `depth` turned out to be wrong, so the solver is going back to try
+    ///something else
+    Backtrack { depth: usize },
+    ///A cell was left with no possible values
+    Contradiction { row: usize, col: usize },
+}
+
+///Receives events from the solver, for logging, animating the solve, gathering statistics and
+///so on. The solver itself never prints anything.
+pub trait SolverObserver {
+    fn on_event(&mut self, event: &SolverEvent);
+}
+
+///Lets a borrowed observer be handed to the solver while the caller keeps hold of it
+impl<O: SolverObserver + ?Sized> SolverObserver for &mut O {
+    fn on_event(&mut self, event: &SolverEvent) {
+        (**self).on_event(event);
+    }
+}
+
+///Ignores every event. What the solver uses when nobody is listening.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoObserver;
+
+impl SolverObserver for NoObserver {
+    fn on_event(&mut self, _event: &SolverEvent) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_deduction::TechniqueSet;
+    use crate::sudoku_grid::SudokuGrid;
+    use crate::sudoku_solver::BacktrackingSolver;
+
+    const ONE_SOLUTION: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    const HARD: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+    //Row 1 holds 1 to 8, and column 9 already has a 9, so r1c9 has nothing left
+    const CLASH: &str =
+        "12345678.........9...............................................................";
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<SolverEvent>,
+    }
+
+    impl SolverObserver for Recorder {
+        fn on_event(&mut self, event: &SolverEvent) {
+            self.events.push(event.clone());
+        }
+    }
+
+    fn record(line: &str, solver: BacktrackingSolver) -> Vec<SolverEvent> {
+        let mut recorder = Recorder::default();
+        let _ = solver.solve_observed(&SudokuGrid::parse_str(line).unwrap(), &mut recorder);
+        return recorder.events;
+    }
+
+    #[test]
+    fn singles_place_every_empty_cell() {
+        let events = record(ONE_SOLUTION, BacktrackingSolver::new());
+        let placements = events
+            .iter()
+            .filter(|e| matches!(e, SolverEvent::Placement { .. }))
+            .count();
+        assert_eq!(placements, ONE_SOLUTION.matches('.').count());
+        assert!(events.iter().all(|e| matches!(
+            e,
+            SolverEvent::Placement { .. } | SolverEvent::Elimination { .. }
+        )));
+        //The last empty cell is filled in once nothing else can go there
+        assert!(matches!(events.last(), Some(SolverEvent::Placement { .. })));
+    }
+
+    #[test]
+    fn guesses_are_followed_through() {
+        let events = record(HARD, BacktrackingSolver::new());
+        let mut depth = 0;
+        let mut backtracks = 0;
+        for event in events.iter() {
+            match event {
+                SolverEvent::Guess { depth: d, .. } => {
+                    assert!(*d >= 1 && *d <= depth + 1);
+                    depth = *d;
+                }
+                SolverEvent::Backtrack { depth: d } => {
+                    assert_eq!(*d, depth);
+                    backtracks += 1;
+                }
+                SolverEvent::GridCloned { depth: d } => assert_eq!(*d, depth + 1),
+                _ => {}
+            }
+        }
+        assert!(backtracks > 0);
+        //Every wrong guess ends in a contradiction, and then the solver goes back
+        let contradictions = events
+            .iter()
+            .filter(|e| matches!(e, SolverEvent::Contradiction { .. }))
+            .count();
+        assert_eq!(contradictions, backtracks);
+    }
+
+    #[test]
+    fn reports_the_contradiction() {
+        let events = record(CLASH, BacktrackingSolver::new());
+        assert_eq!(events, vec![SolverEvent::Contradiction { row: 0, col: 8 }]);
+    }
+
+    #[test]
+    fn deductions_are_followed_by_their_eliminations() {
+        let solver = BacktrackingSolver::new().with_techniques(TechniqueSet::safe());
+        let events = record(HARD, solver);
+        let mut deductions = 0;
+        for (i, event) in events.iter().enumerate() {
+            if let SolverEvent::Deduction(deduction) = event {
+                deductions += 1;
+                let eliminations: Vec<SolverEvent> = deduction
+                    .eliminations
+                    .iter()
+                    .map(|c| SolverEvent::Elimination {
+                        row: c.row,
+                        col: c.col,
+                        value: c.value,
+                    })
+                    .collect();
+                assert_eq!(
+                    events[i + 1..i + 1 + eliminations.len()].to_vec(),
+                    eliminations
+                );
+            }
+        }
+        assert!(deductions > 0);
+    }
+}
@@ -0,0 +1,375 @@
+use super::sudoku_deduction::{
+    positions, Candidate, Deduction, Pattern, StrongLink, Technique, Unit,
+};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_units::{col_of, row_of, sees, subgrid_of, COL_UNIT, SUBGRID_UNIT, UNITS};
+
+//Patterns made of strong links on a single digit. A strong link is a unit where the digit only
+//has two places left, so if one of them isn't the digit the other one has to be.
+
+impl SudokuGrid {
+    ///Every strong link on the digit, as (unit, first cell, second cell)
+    pub(crate) fn strong_links(&self, digit: usize) -> Vec<(usize, usize, usize)> {
+        let mut links = Vec::new();
+        for unit in 0..UNITS.len() {
+            let places = self.places_in(unit, digit);
+            if places.len() == 2 {
+                links.push((unit, places[0], places[1]));
+            }
+        }
+        return links;
+    }
+
+    ///A Skyscraper is two strong links in parallel rows (or columns) with one end of each on
+    ///the same column (or row). Those two ends can't both be the digit, so one of the other ends
+    ///has to be, and no cell that sees both of them can be.
+    pub(crate) fn find_skyscraper(&self) -> Option<Deduction> {
+        return self.find_two_links(Technique::Skyscraper, |first, second, base| {
+            let (a, b) = (first.0, second.0);
+            let parallel = (a < COL_UNIT && b < COL_UNIT)
+                || ((COL_UNIT..SUBGRID_UNIT).contains(&a) && (COL_UNIT..SUBGRID_UNIT).contains(&b));
+            let crossing = |i: usize| if a < COL_UNIT { col_of(i) } else { row_of(i) };
+            return parallel && crossing(base.0) == crossing(base.1);
+        });
+    }
+
+    ///A 2-String Kite is a strong link in a row and one in a column, with one end of each in the
+    ///same subgrid. Those two ends can't both be the digit, so one of the other ends has to be.
+    pub(crate) fn find_two_string_kite(&self) -> Option<Deduction> {
+        return self.find_two_links(Technique::TwoStringKite, |first, second, base| {
+            let (a, b) = (first.0, second.0);
+            let row_and_column = a < COL_UNIT && (COL_UNIT..SUBGRID_UNIT).contains(&b);
+            return row_and_column && subgrid_of(base.0) == subgrid_of(base.1);
+        });
+    }
+
+    ///A Turbot Fish is any two strong links with one end of each seeing the other, including
+    ///links in subgrids. Skyscrapers and 2-String Kites are the commonest kinds.
+    pub(crate) fn find_turbot_fish(&self) -> Option<Deduction> {
+        return self.find_two_links(Technique::TurbotFish, |_, _, _| true);
+    }
+
+    ///Looks for two strong links on a digit where an end of the first sees an end of the
+    ///second, and the `fits` test accepts the links and those two ends. The other two ends are
+    ///the ones that matter: one of them has to be the digit.
+    fn find_two_links<F>(&self, technique: Technique, fits: F) -> Option<Deduction>
+    where
+        F: Fn((usize, usize, usize), (usize, usize, usize), (usize, usize)) -> bool,
+    {
+        for digit in 1..=9 {
+            let links = self.strong_links(digit);
+            for first in links.iter() {
+                for second in links.iter() {
+                    if first.0 == second.0 {
+                        continue;
+                    }
+                    for (base_1, top_1) in [(first.1, first.2), (first.2, first.1)].iter() {
+                        for (base_2, top_2) in [(second.1, second.2), (second.2, second.1)].iter() {
+                            let cells = [*base_1, *top_1, *base_2, *top_2];
+                            let distinct = (0..4).all(|i| (i + 1..4).all(|j| cells[i] != cells[j]));
+                            if !distinct
+                                || !sees(*base_1, *base_2)
+                                || !fits(*first, *second, (*base_1, *base_2))
+                            {
+                                continue;
+                            }
+                            let eliminations = self.seen_by_all(&[*top_1, *top_2], digit);
+                            if !eliminations.is_empty() {
+                                return Some(Deduction {
+                                    technique,
+                                    pattern: Pattern::StrongLinks {
+                                        digit,
+                                        links: vec![
+                                            StrongLink::between(first.0, &[*top_1], &[*base_1]),
+                                            StrongLink::between(second.0, &[*base_2], &[*top_2]),
+                                        ],
+                                    },
+                                    eliminations,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///An Empty Rectangle is a subgrid where every place for the digit lies on one row and one
+    ///column of the subgrid, together with a strong link on a line outside the subgrid that has
+    ///one end on that row (or column). If the cell where the other end's row (or column) meets
+    ///the subgrid's column (or row) were the digit, the subgrid would have nowhere left for it.
+    pub(crate) fn find_empty_rectangle(&self) -> Option<Deduction> {
+        for digit in 1..=9 {
+            let links = self.strong_links(digit);
+            for subgrid in 0..9 {
+                let places = self.places_in(SUBGRID_UNIT + subgrid, digit);
+                if places.len() < 2 {
+                    continue;
+                }
+                let box_row = (subgrid / 3) * 3;
+                let box_col = (subgrid % 3) * 3;
+
+                for row in box_row..box_row + 3 {
+                    for col in box_col..box_col + 3 {
+                        if !places
+                            .iter()
+                            .all(|i| row_of(*i) == row || col_of(*i) == col)
+                        {
+                            continue;
+                        }
+                        //Places on only one of the lines are left to pointing
+                        if places.iter().all(|i| row_of(*i) == row)
+                            || places.iter().all(|i| col_of(*i) == col)
+                        {
+                            continue;
+                        }
+
+                        for (unit, a, b) in links.iter() {
+                            for (near, far) in [(*a, *b), (*b, *a)].iter() {
+                                //A column link meeting the subgrid's row, or a row link meeting
+                                //its column, from outside the subgrid
+                                let target = if (COL_UNIT..SUBGRID_UNIT).contains(unit)
+                                    && row_of(*near) == row
+                                    && !(box_col..box_col + 3).contains(&col_of(*near))
+                                {
+                                    row_of(*far) * 9 + col
+                                } else if *unit < COL_UNIT
+                                    && col_of(*near) == col
+                                    && !(box_row..box_row + 3).contains(&row_of(*near))
+                                {
+                                    row * 9 + col_of(*far)
+                                } else {
+                                    continue;
+                                };
+                                if subgrid_of(target) == subgrid
+                                    || !self.candidates_at(target).contains(digit)
+                                {
+                                    continue;
+                                }
+
+                                let (on_row, on_col): (Vec<usize>, Vec<usize>) =
+                                    places.iter().partition(|i| row_of(**i) == row);
+                                return Some(Deduction {
+                                    technique: Technique::EmptyRectangle,
+                                    pattern: Pattern::StrongLinks {
+                                        digit,
+                                        links: vec![
+                                            StrongLink::between(
+                                                SUBGRID_UNIT + subgrid,
+                                                &on_row,
+                                                &on_col,
+                                            ),
+                                            StrongLink::between(*unit, &[*near], &[*far]),
+                                        ],
+                                    },
+                                    eliminations: vec![Candidate::at(target, digit)],
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+}
+
+impl StrongLink {
+    pub(crate) fn between(unit: usize, first: &[usize], second: &[usize]) -> StrongLink {
+        return StrongLink {
+            unit: Unit::from_index(unit),
+            first: positions(first),
+            second: positions(second),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skyscraper() {
+        //Partway through solving
+        //.......3..1.8..2......54..94......27..5........8.27.1.5......71......4..7..4.29.6
+        let grid = SudokuGrid::from_candidates(
+            "
+            69 5  4  2   69  1   7   3 8
+            3  1  69 8   7   69  2   4 5
+            8  2  7  3   5   4   1   6 9
+            4  69 1  59  689 3   568 2 7
+            2  7  5  1   4   68  68  9 3
+            69 3  8  569 2   7   56  1 4
+            5  4  2  69  689 689 3   7 1
+            1  69 69 7   3   5   4   8 2
+            7  8  3  4   1   2   9   5 6
+            ",
+        );
+        assert_eq!(
+            grid.find_skyscraper(),
+            Some(Deduction {
+                technique: Technique::Skyscraper,
+                pattern: Pattern::StrongLinks {
+                    digit: 9,
+                    links: vec![
+                        StrongLink {
+                            unit: Unit::Row(0),
+                            first: vec![(0, 4)],
+                            second: vec![(0, 0)],
+                        },
+                        StrongLink {
+                            unit: Unit::Row(5),
+                            first: vec![(5, 0)],
+                            second: vec![(5, 3)],
+                        },
+                    ],
+                },
+                eliminations: vec![Candidate::new(3, 4, 9)],
+            })
+        );
+    }
+
+    #[test]
+    fn two_string_kite() {
+        //Partway through solving
+        //152....6.3.........4912........4..73...53.9...7....81.....1..3.......6...6...5..4
+        let grid = SudokuGrid::from_candidates(
+            "
+            1   5 2   9   78 3   4 6 78
+            3   8 67  467 5  46  1 9 2
+            67  4 9   1   2  68  3 5 78
+            56  9 56  8   4  1   2 7 3
+            2   1 8   5   3  7   9 4 6
+            4   7 3   2   6  9   8 1 5
+            78  2 47  67  1  468 5 3 9
+            579 3 457 47  79 2   6 8 1
+            89  6 1   3   89 5   7 2 4
+            ",
+        );
+        assert_eq!(
+            grid.find_two_string_kite(),
+            Some(Deduction {
+                technique: Technique::TwoStringKite,
+                pattern: Pattern::StrongLinks {
+                    digit: 7,
+                    links: vec![
+                        StrongLink {
+                            unit: Unit::Row(1),
+                            first: vec![(1, 2)],
+                            second: vec![(1, 3)],
+                        },
+                        StrongLink {
+                            unit: Unit::Column(4),
+                            first: vec![(0, 4)],
+                            second: vec![(7, 4)],
+                        },
+                    ],
+                },
+                eliminations: vec![Candidate::new(7, 2, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn turbot_fish() {
+        //Partway through solving
+        //3.6...9....7........29....4.6...8.2.....7.64.9.......341.8...9..2......77...13...
+        let grid = SudokuGrid::from_candidates(
+            "
+            3  4 6   7   28  12  9  5  18
+            18 9 7   145 458 145 2  3  6
+            18 5 2   9   3   6   18 7  4
+            5  6 14  3   49  8   7  2  19
+            2  3 18  15  7   159 6  4  89
+            9  7 148 6   24  124 5  18 3
+            4  1 5   8   6   7   3  9  2
+            6  2 3   45  459 459 18 18 7
+            7  8 9   2   1   3   4  6  5
+            ",
+        );
+        assert_eq!(
+            grid.find_turbot_fish(),
+            Some(Deduction {
+                technique: Technique::TurbotFish,
+                pattern: Pattern::StrongLinks {
+                    digit: 1,
+                    links: vec![
+                        StrongLink {
+                            unit: Unit::Row(0),
+                            first: vec![(0, 5)],
+                            second: vec![(0, 8)],
+                        },
+                        StrongLink {
+                            unit: Unit::Subgrid(5),
+                            first: vec![(3, 8)],
+                            second: vec![(5, 7)],
+                        },
+                    ],
+                },
+                eliminations: vec![Candidate::new(5, 5, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn empty_rectangle() {
+        //Partway through solving
+        //..76.1..93....7...59.2...4..86.4..151.....7.............8.6.5.......8...43......1
+        let grid = SudokuGrid::from_candidates(
+            "
+            8  4   7 6  5  1  23 23  9
+            3  6   2 4  9  7  1  5   8
+            5  9   1 2  8  3  6  4   7
+            27 8   6 37 4  9  23 1   5
+            1  25  4 8  23 56 7  9   236
+            9  257 3 17 12 56 4  8   26
+            27 1   8 9  6  4  5  237 23
+            6  27  5 13 13 8  9  27  4
+            4  3   9 5  7  2  8  6   1
+            ",
+        );
+        assert_eq!(
+            grid.find_empty_rectangle(),
+            Some(Deduction {
+                technique: Technique::EmptyRectangle,
+                pattern: Pattern::StrongLinks {
+                    digit: 2,
+                    links: vec![
+                        StrongLink {
+                            unit: Unit::Subgrid(5),
+                            first: vec![(3, 6)],
+                            second: vec![(4, 8), (5, 8)],
+                        },
+                        StrongLink {
+                            unit: Unit::Column(0),
+                            first: vec![(3, 0)],
+                            second: vec![(6, 0)],
+                        },
+                    ],
+                },
+                eliminations: vec![Candidate::new(6, 8, 2)],
+            })
+        );
+    }
+
+    #[test]
+    fn strong_links_are_units_with_two_places() {
+        //1 can only go in r1c1 and r1c5 of row 1. Every other unit still has more places for it.
+        let mut grid = SudokuGrid::new();
+        for col in [1, 2, 3, 5, 6, 7, 8].iter() {
+            grid.remove_candidate(*col, 1);
+        }
+        assert_eq!(grid.strong_links(1), vec![(0, 0, 4)]);
+        assert!(grid.strong_links(2).is_empty());
+    }
+
+    #[test]
+    fn nothing_without_strong_links() {
+        let grid = SudokuGrid::new();
+        assert_eq!(grid.find_skyscraper(), None);
+        assert_eq!(grid.find_two_string_kite(), None);
+        assert_eq!(grid.find_turbot_fish(), None);
+        assert_eq!(grid.find_empty_rectangle(), None);
+    }
+}
@@ -0,0 +1,209 @@
+use super::sudoku_deduction::TechniqueSet;
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_observer::{SolverEvent, SolverObserver};
+use super::sudoku_solver::{BacktrackingSolver, BranchStrategy};
+
+///Iterates over every solution of a grid, finding each one only when it is asked for.
+///
+///This is the guessing approach described on `solve_grid`. Instead of recursing into a clone for
+///each guess, the grids still to be tried are kept on a stack. Only the guesses along the
+///current path (at most 9 per unsolved cell) are ever held in memory, never the solutions that
+///have already been returned.
+pub struct Solutions<'a> {
+    stack: Vec<Branch>,
+    strategy: BranchStrategy,
+    techniques: TechniqueSet,
+    observer: Box<dyn SolverObserver + 'a>,
+}
+
+///A grid waiting on the stack, along with the guess to make in it
+struct Branch {
+    grid: SudokuGrid,
+    guess: Option<(usize, usize)>,
+    depth: usize,
+    ///Whether deduction has already gone as far as it can in the grid
+    deduced: bool,
+}
+
+impl<'a> Solutions<'a> {
+    pub(crate) fn new(
+        grid: SudokuGrid,
+        strategy: BranchStrategy,
+        techniques: TechniqueSet,
+        observer: Box<dyn SolverObserver + 'a>,
+    ) -> Self {
+        Solutions {
+            stack: vec![Branch {
+                grid,
+                guess: None,
+                depth: 0,
+                deduced: false,
+            }],
+            strategy,
+            techniques,
+            observer,
+        }
+    }
+
+    ///Like `new`, for a grid the techniques have already been applied to but that isn't solved,
+    ///so the search starts straight away with a guess
+    pub(crate) fn after_deduction(
+        grid: SudokuGrid,
+        strategy: BranchStrategy,
+        techniques: TechniqueSet,
+        observer: Box<dyn SolverObserver + 'a>,
+    ) -> Self {
+        let mut solutions = Solutions::new(grid, strategy, techniques, observer);
+        solutions.stack[0].deduced = true;
+        return solutions;
+    }
+}
+
+impl<'a> Iterator for Solutions<'a> {
+    type Item = SudokuGrid;
+
+    fn next(&mut self) -> Option<SudokuGrid> {
+        while let Some(branch) = self.stack.pop() {
+            let mut grid = branch.grid;
+            if let Some((index, value)) = branch.guess {
+                self.observer.on_event(&SolverEvent::Guess {
+                    row: index / 9,
+                    col: index % 9,
+                    value,
+                    depth: branch.depth,
+                });
+                grid.place_value_observed(index, value, self.observer.as_mut());
+            }
+
+            let deduced = if branch.deduced {
+                Ok(false)
+            } else {
+                grid.deduce(self.techniques, self.observer.as_mut())
+            };
+            match deduced {
+                Ok(true) => return Some(grid),
+                Ok(false) => {}
+                //One of the guesses that got us here was wrong
+                Err(_) => {
+                    if branch.depth > 0 {
+                        self.observer.on_event(&SolverEvent::Backtrack {
+                            depth: branch.depth,
+                        });
+                    }
+                    continue;
+                }
+            }
+
+            //Pushed in reverse, so the guesses are tried in the order they were given
+            for guess in grid.choose_branch(self.strategy).into_iter().rev() {
+                self.observer.on_event(&SolverEvent::GridCloned {
+                    depth: branch.depth + 1,
+                });
+                self.stack.push(Branch {
+                    grid: grid.clone(),
+                    guess: Some(guess),
+                    depth: branch.depth + 1,
+                    deduced: false,
+                });
+            }
+        }
+        return None;
+    }
+}
+
+impl SudokuGrid {
+    ///All of the grid's solutions, found lazily. Use `take` to only look at the first few.
+    pub fn solutions(&self) -> Solutions<'static> {
+        return BacktrackingSolver::new().solutions(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_units::UNITS;
+    use std::collections::HashSet;
+
+    //Has three solutions
+    const SEVERAL_SOLUTIONS: &str =
+        ".61...8......5...384.76..5..5..7.36.7..3.5..1..4.2.....8..96.3.3...475...72......";
+    const NO_SOLUTION: &str =
+        "261...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+
+    fn grid(line: &str) -> SudokuGrid {
+        return SudokuGrid::parse_str(line).unwrap();
+    }
+
+    ///Checks every unit holds 1 to 9 once, and that the givens of the puzzle are kept
+    fn assert_solves(solution: &SudokuGrid, puzzle: &str) {
+        let line = solution.to_line();
+        for unit in UNITS.iter() {
+            let digits: HashSet<u8> = unit.iter().map(|&i| line.as_bytes()[i]).collect();
+            assert_eq!(digits.len(), 9, "{} has a repeated digit", line);
+        }
+        for (given, value) in puzzle.bytes().zip(line.bytes()) {
+            assert!(
+                given == b'.' || given == value,
+                "{} doesn't solve {}",
+                line,
+                puzzle
+            );
+        }
+    }
+
+    #[test]
+    fn finds_every_solution_once() {
+        let solutions: Vec<String> = grid(SEVERAL_SOLUTIONS)
+            .solutions()
+            .inspect(|solution| assert_solves(solution, SEVERAL_SOLUTIONS))
+            .map(|solution| solution.to_line())
+            .collect();
+        assert_eq!(solutions.len(), 3);
+        assert_eq!(solutions.iter().collect::<HashSet<_>>().len(), 3);
+    }
+
+    #[test]
+    fn only_searches_as_far_as_asked() {
+        //The empty grid has far too many solutions to find them all
+        let empty = ".".repeat(81);
+        let mut solutions = SudokuGrid::new().solutions();
+        let first: Vec<SudokuGrid> = solutions.by_ref().take(5).collect();
+        assert_eq!(first.len(), 5);
+        for solution in first.iter() {
+            assert_solves(solution, &empty);
+        }
+        let sixth = solutions.next().unwrap();
+        assert!(first.iter().all(|s| s.to_line() != sixth.to_line()));
+    }
+
+    #[test]
+    fn nothing_for_an_unsolvable_grid() {
+        let mut solutions = grid(NO_SOLUTION).solutions();
+        assert!(solutions.next().is_none());
+        assert!(solutions.next().is_none());
+    }
+
+    #[test]
+    fn every_strategy_finds_the_same_solutions() {
+        let strategies = [
+            BranchStrategy::FirstEmpty,
+            BranchStrategy::FewestCandidates,
+            BranchStrategy::MostConstrainedUnit,
+            BranchStrategy::HiddenSingleInUnit,
+        ];
+        let mut expected: Vec<String> = grid(SEVERAL_SOLUTIONS)
+            .solutions()
+            .map(|solution| solution.to_line())
+            .collect();
+        expected.sort();
+        for strategy in strategies.iter() {
+            let solver = BacktrackingSolver::new().with_strategy(*strategy);
+            let mut found: Vec<String> = solver
+                .solutions(&grid(SEVERAL_SOLUTIONS))
+                .map(|solution| solution.to_line())
+                .collect();
+            found.sort();
+            assert_eq!(found, expected, "{:?}", strategy);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use super::sudoku_deduction::{Technique, TechniqueSet};
+use super::sudoku_error::SudokuError;
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_observer::{NoObserver, SolverObserver};
+use super::sudoku_solutions::Solutions;
+use super::sudoku_units::UNITS;
+
+///A way of solving grids. Every solver finds the same solutions, so they can be swapped for one
+///another, or used to check each other's answers.
+pub trait SudokuSolver {
+    ///Fills in the rest of the grid.
+    ///Returns `SudokuError::SearchExhausted` if it has no solution, or
+    ///`SudokuError::Contradiction` if that is already obvious from the givens.
+    fn solve(&self, grid: &SudokuGrid) -> Result<SudokuGrid, SudokuError>;
+
+    ///Counts the grid's solutions, giving up once `limit` of them have been found
+    fn count_solutions(&self, grid: &SudokuGrid, limit: usize) -> usize;
+
+    ///Returns true if the grid has exactly one solution
+    fn is_unique(&self, grid: &SudokuGrid) -> bool {
+        return self.count_solutions(grid, 2) == 1;
+    }
+}
+
+///How the solver picks what to guess once deduction stops making progress.
+///Every strategy lists all of the ways the guess could go, so the search finds the same
+///solutions whichever is used; they only differ in how quickly it gets there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchStrategy {
+    ///Guess the values of the first empty cell in reading order
+    FirstEmpty,
+    ///Guess the values of the empty cell with the fewest possible values
+    FewestCandidates,
+    ///Pick the row, column or subgrid with the fewest empty cells, then guess the values of its
+    ///empty cell with the fewest possible values
+    MostConstrainedUnit,
+    ///Pick the digit that has the fewest places left in some row, column or subgrid, then guess
+    ///each of those places in turn. The digit has to go in one of them, just like a hidden
+    ///single, only with more than one place to choose from.
+    HiddenSingleInUnit,
+}
+
+impl Default for BranchStrategy {
+    fn default() -> Self {
+        return BranchStrategy::FewestCandidates;
+    }
+}
+
+impl SudokuGrid {
+    ///The guesses to try, as (cell index, value) pairs. One of them has to be right if the grid
+    ///has a solution at all. Empty if the grid is already solved.
+    pub(crate) fn choose_branch(&self, strategy: BranchStrategy) -> Vec<(usize, usize)> {
+        let cell = match strategy {
+            BranchStrategy::FirstEmpty => self.get_next_unsolved_cell_index(),
+            BranchStrategy::FewestCandidates => self.fewest_candidates_in(0..81),
+            BranchStrategy::MostConstrainedUnit => {
+                let unit = UNITS
+                    .iter()
+                    .filter(|u| self.empty_cells_in(u) > 0)
+                    .min_by_key(|u| self.empty_cells_in(u));
+                match unit {
+                    Some(u) => self.fewest_candidates_in(u.iter().cloned()),
+                    None => None,
+                }
+            }
+            BranchStrategy::HiddenSingleInUnit => return self.fewest_places_in_unit(),
+        };
+
+        return match cell {
+            Some(s) => self.cells()[s]
+                .candidates()
+                .iter()
+                .map(|v| (s, v))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    fn empty_cells_in(&self, unit: &[usize; 9]) -> usize {
+        return unit
+            .iter()
+            .filter(|i| self.cells()[**i].value().is_none())
+            .count();
+    }
+
+    fn fewest_candidates_in<I: Iterator<Item = usize>>(&self, indexes: I) -> Option<usize> {
+        let mut best: Option<(usize, usize)> = None;
+        for i in indexes {
+            let cell = &self.cells()[i];
+            if cell.value().is_some() {
+                continue;
+            }
+            let count = cell.candidates().len();
+            if count < best.map_or(usize::MAX, |(_, c)| c) {
+                best = Some((i, count));
+                //A cell can't have fewer than two possible values once deduction is done
+                if count <= 2 {
+                    break;
+                }
+            }
+        }
+        return best.map(|(i, _)| i);
+    }
+
+    fn fewest_places_in_unit(&self) -> Vec<(usize, usize)> {
+        let mut best: Vec<(usize, usize)> = Vec::new();
+        for unit in UNITS.iter() {
+            for digit in 1..=9 {
+                let places = unit
+                    .iter()
+                    .filter(|i| {
+                        let cell = &self.cells()[**i];
+                        cell.value().is_none() && cell.candidates().contains(digit)
+                    })
+                    .map(|i| (*i, digit))
+                    .collect::<Vec<(usize, usize)>>();
+                if !places.is_empty() && (best.is_empty() || places.len() < best.len()) {
+                    best = places;
+                    if best.len() <= 2 {
+                        return best;
+                    }
+                }
+            }
+        }
+        return best;
+    }
+}
+
+///Solves grids by deduction, guessing when deduction gets stuck and going back when a guess
+///turns out to be wrong. This is what `SudokuGrid::solve_grid` uses, with the default strategy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BacktrackingSolver {
+    strategy: BranchStrategy,
+    techniques: TechniqueSet,
+    unique: bool,
+}
+
+impl BacktrackingSolver {
+    pub fn new() -> Self {
+        return BacktrackingSolver::default();
+    }
+
+    ///Use a different way of picking what to guess
+    pub fn with_strategy(mut self, strategy: BranchStrategy) -> Self {
+        self.strategy = strategy;
+        return self;
+    }
+
+    pub fn strategy(&self) -> BranchStrategy {
+        return self.strategy;
+    }
+
+    ///Use only the given techniques, besides the singles, before guessing. By default that is
+    ///just the singles, which solves fastest; pass `TechniqueSet::safe()` to see every step of
+    ///logic there is, as when rating a puzzle. The ones that need a unique solution are only used
+    ///if the solver also assumes there is one, see `assume_unique_solution`. The solutions found
+    ///are the same either way, fewer techniques only means more guessing. They are only used to
+    ///solve, counting and listing solutions only ever uses the singles.
+    pub fn with_techniques(mut self, techniques: TechniqueSet) -> Self {
+        self.techniques = techniques;
+        return self;
+    }
+
+    pub fn techniques(&self) -> TechniqueSet {
+        return self.techniques;
+    }
+
+    ///Whether to assume the puzzles solved have exactly one solution, which lets the solver use
+    ///the techniques that rely on it (see `Technique::needs_unique_solution`). Off by default,
+    ///since they can rule out real solutions of a puzzle with more than one. Counting and listing
+    ///solutions never use them.
+    ///
+    ///Turning it on adds those techniques to the solver's. To use only some of them, pass the
+    ///ones wanted to `with_techniques` afterwards.
+    pub fn assume_unique_solution(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        if unique {
+            for technique in Technique::ALL.iter().filter(|t| t.needs_unique_solution()) {
+                self.techniques.insert(*technique);
+            }
+        }
+        return self;
+    }
+
+    pub fn assumes_unique_solution(&self) -> bool {
+        return self.unique;
+    }
+
+    ///The techniques to solve with, without the ones that need a unique solution unless the
+    ///solver assumes there is one
+    fn solving_techniques(&self) -> TechniqueSet {
+        return self
+            .techniques
+            .iter()
+            .filter(|t| !t.needs_unique_solution() || self.unique)
+            .collect();
+    }
+
+    ///All of the grid's solutions, found lazily
+    pub fn solutions(&self, grid: &SudokuGrid) -> Solutions<'static> {
+        return self.solutions_observed(grid, NoObserver);
+    }
+
+    ///All of the grid's solutions, found lazily, with `observer` told about every step the
+    ///search takes.
+    ///
+    ///Only the singles are used here, whatever techniques the solver was given. The others can't
+    ///change which solutions there are, and looking for them at every guess costs far more than
+    ///the guesses they save when every solution has to be found anyway.
+    pub fn solutions_observed<'a, O: SolverObserver + 'a>(
+        &self,
+        grid: &SudokuGrid,
+        observer: O,
+    ) -> Solutions<'a> {
+        return Solutions::new(
+            grid.clone(),
+            self.strategy,
+            TechniqueSet::empty(),
+            Box::new(observer),
+        );
+    }
+
+    ///Fills in the rest of the grid, with `observer` told about every step along the way.
+    ///Pass `&mut observer` to keep hold of it afterwards.
+    pub fn solve_observed<O: SolverObserver>(
+        &self,
+        grid: &SudokuGrid,
+        mut observer: O,
+    ) -> Result<SudokuGrid, SudokuError> {
+        let techniques = self.solving_techniques();
+        let mut grid = grid.clone();
+        //Deduce before guessing anything, so a puzzle that is broken as given is reported as such
+        if grid.deduce(techniques, &mut observer)? {
+            return Ok(grid);
+        }
+
+        let mut solutions =
+            Solutions::after_deduction(grid, self.strategy, techniques, Box::new(&mut observer));
+        return match solutions.next() {
+            Some(solved) => Ok(solved),
+            None => Err(SudokuError::SearchExhausted),
+        };
+    }
+}
+
+impl SudokuSolver for BacktrackingSolver {
+    ///Fills in the rest of the grid, see `SudokuGrid::solve_grid`
+    fn solve(&self, grid: &SudokuGrid) -> Result<SudokuGrid, SudokuError> {
+        return self.solve_observed(grid, NoObserver);
+    }
+
+    fn count_solutions(&self, grid: &SudokuGrid, limit: usize) -> usize {
+        return self.solutions(grid).take(limit).count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HARD: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn strategies_pick_different_guesses() {
+        let mut grid = SudokuGrid::new();
+        //r5c5 can only be 3 or 5
+        for value in [1, 2, 4, 6, 7, 8, 9].iter() {
+            grid.remove_candidate(40, *value);
+        }
+        //6 can only go in r1c1 or r1c5 of the first row
+        for col in [1, 2, 3, 5, 6, 7, 8].iter() {
+            grid.remove_candidate(*col, 6);
+        }
+
+        let first: Vec<(usize, usize)> = (1..=9).map(|v| (0, v)).collect();
+        assert_eq!(grid.choose_branch(BranchStrategy::FirstEmpty), first);
+        assert_eq!(
+            grid.choose_branch(BranchStrategy::FewestCandidates),
+            vec![(40, 3), (40, 5)]
+        );
+        assert_eq!(
+            grid.choose_branch(BranchStrategy::HiddenSingleInUnit),
+            vec![(0, 6), (4, 6)]
+        );
+
+        //The last row has one empty cell left, which beats r5c5's two possible values
+        for col in 0..8 {
+            grid.place_value(72 + col, col + 1);
+        }
+        assert_eq!(
+            grid.choose_branch(BranchStrategy::MostConstrainedUnit),
+            vec![(80, 9)]
+        );
+    }
+
+    #[test]
+    fn nothing_to_guess_once_solved() {
+        let solved = SudokuGrid::parse_str(HARD).unwrap().solve_grid().unwrap();
+        assert!(solved.choose_branch(BranchStrategy::default()).is_empty());
+        assert!(solved
+            .choose_branch(BranchStrategy::HiddenSingleInUnit)
+            .is_empty());
+    }
+
+    #[test]
+    fn uniqueness_techniques_only_when_assuming_one_solution() {
+        let unique = Technique::UniqueRectangleType1;
+        let solver = BacktrackingSolver::new().with_techniques(TechniqueSet::all());
+        assert!(!solver.solving_techniques().contains(unique));
+        assert!(solver.solving_techniques().contains(Technique::XWing));
+
+        let solver = BacktrackingSolver::new().assume_unique_solution(true);
+        assert!(solver.techniques().contains(unique));
+        assert!(solver.solving_techniques().contains(Technique::BugPlusOne));
+        assert!(!solver.solving_techniques().contains(Technique::XWing));
+
+        //Techniques given afterwards replace the ones assuming a solution added
+        let solver = solver.with_techniques(TechniqueSet::safe());
+        assert!(solver.assumes_unique_solution());
+        assert_eq!(solver.solving_techniques(), TechniqueSet::safe());
+    }
+
+    #[test]
+    fn every_strategy_solves_the_same() {
+        let grid = SudokuGrid::parse_str(HARD).unwrap();
+        let expected = grid.clone().solve_grid().unwrap().to_line();
+        for strategy in [
+            BranchStrategy::FirstEmpty,
+            BranchStrategy::FewestCandidates,
+            BranchStrategy::MostConstrainedUnit,
+            BranchStrategy::HiddenSingleInUnit,
+        ]
+        .iter()
+        {
+            let solver = BacktrackingSolver::new().with_strategy(*strategy);
+            assert_eq!(solver.strategy(), *strategy);
+            assert_eq!(solver.solve(&grid).unwrap().to_line(), expected);
+            assert!(solver.is_unique(&grid));
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use super::sudoku_deduction::Technique;
+use super::sudoku_error::SudokuError;
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_observer::{SolverEvent, SolverObserver};
+use super::sudoku_solver::BacktrackingSolver;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+///What it took to solve a grid. Puzzles that need more guessing, or only give in to the harder
+///techniques, are the harder puzzles.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    placements: BTreeMap<Technique, usize>,
+    deductions: BTreeMap<Technique, usize>,
+    eliminations: usize,
+    guesses: usize,
+    backtracks: usize,
+    max_depth: usize,
+    clones: usize,
+    elapsed: Duration,
+}
+
+impl SolveStats {
+    pub fn new() -> Self {
+        return SolveStats::default();
+    }
+
+    ///Values placed by the given technique
+    pub fn placements(&self, technique: Technique) -> usize {
+        return *self.placements.get(&technique).unwrap_or(&0);
+    }
+
+    ///Values placed by deduction, whatever the technique
+    pub fn total_placements(&self) -> usize {
+        return self.placements.values().sum();
+    }
+
+    ///Times the given technique ruled out possible values. Always 0 for the singles, which
+    ///place values instead.
+    pub fn deductions(&self, technique: Technique) -> usize {
+        return *self.deductions.get(&technique).unwrap_or(&0);
+    }
+
+    ///The hardest technique that was needed, if deduction found anything at all
+    pub fn hardest_technique(&self) -> Option<Technique> {
+        return self
+            .placements
+            .keys()
+            .chain(self.deductions.keys())
+            .max()
+            .cloned();
+    }
+
+    ///Possible values taken out of cells
+    pub fn eliminations(&self) -> usize {
+        return self.eliminations;
+    }
+
+    pub fn guesses(&self) -> usize {
+        return self.guesses;
+    }
+
+    ///Guesses that turned out to be wrong
+    pub fn backtracks(&self) -> usize {
+        return self.backtracks;
+    }
+
+    ///Most guesses the solver had made at once, 0 if it never had to guess
+    pub fn max_depth(&self) -> usize {
+        return self.max_depth;
+    }
+
+    ///Copies of the grid made to hold guesses
+    pub fn clones(&self) -> usize {
+        return self.clones;
+    }
+
+    ///How long the solve took. Only set by `BacktrackingSolver::solve_with_stats`, the events
+    ///themselves carry no timing.
+    pub fn elapsed(&self) -> Duration {
+        return self.elapsed;
+    }
+}
+
+impl SolverObserver for SolveStats {
+    fn on_event(&mut self, event: &SolverEvent) {
+        match event {
+            SolverEvent::Placement { technique, .. } => {
+                *self.placements.entry(*technique).or_insert(0) += 1;
+            }
+            SolverEvent::Deduction(deduction) => {
+                *self.deductions.entry(deduction.technique).or_insert(0) += 1;
+            }
+            SolverEvent::Elimination { .. } => self.eliminations += 1,
+            SolverEvent::Guess { depth, .. } => {
+                self.guesses += 1;
+                self.max_depth = self.max_depth.max(*depth);
+            }
+            SolverEvent::GridCloned { .. } => self.clones += 1,
+            SolverEvent::Backtrack { .. } => self.backtracks += 1,
+            SolverEvent::Contradiction { .. } => {}
+        }
+    }
+}
+
+impl fmt::Display for SolveStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //Written as "name: count", so no name needs a plural
+        for (technique, count) in self.placements.iter().chain(self.deductions.iter()) {
+            write!(f, "{}: {}, ", technique, count)?;
+        }
+        return write!(
+            f,
+            "eliminations: {}, guesses: {}, backtracks: {}, depth: {}, clones: {}, time: {:.3}ms",
+            self.eliminations,
+            self.guesses,
+            self.backtracks,
+            self.max_depth,
+            self.clones,
+            self.elapsed.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+impl BacktrackingSolver {
+    ///Fills in the rest of the grid, the same as `SudokuSolver::solve`, and reports what it
+    ///took. The stats are returned even when the grid has no solution.
+    pub fn solve_with_stats(
+        &self,
+        grid: &SudokuGrid,
+    ) -> (Result<SudokuGrid, SudokuError>, SolveStats) {
+        let mut stats = SolveStats::new();
+        let start = Instant::now();
+        let result = self.solve_observed(grid, &mut stats);
+        stats.elapsed = start.elapsed();
+        return (result, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_deduction::{Deduction, Pattern, Unit};
+
+    const ONE_SOLUTION: &str =
+        ".61...87....45...384.76..5..5..7.36.7..385..1..4.2.....8..96.3.3...475...72....4.";
+    const HARD: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn counts_what_the_events_say() {
+        let mut stats = SolveStats::new();
+        stats.on_event(&SolverEvent::Placement {
+            row: 0,
+            col: 0,
+            value: 5,
+            technique: Technique::HiddenSingle,
+        });
+        stats.on_event(&SolverEvent::Deduction(Deduction {
+            technique: Technique::Pointing,
+            pattern: Pattern::LockedCandidates {
+                digit: 4,
+                from: Unit::Subgrid(0),
+                to: Unit::Row(0),
+            },
+            eliminations: Vec::new(),
+        }));
+        stats.on_event(&SolverEvent::Elimination {
+            row: 0,
+            col: 5,
+            value: 4,
+        });
+        for depth in [1, 2, 1].iter() {
+            stats.on_event(&SolverEvent::GridCloned { depth: *depth });
+            stats.on_event(&SolverEvent::Guess {
+                row: 1,
+                col: 1,
+                value: 1,
+                depth: *depth,
+            });
+        }
+        stats.on_event(&SolverEvent::Backtrack { depth: 2 });
+        stats.on_event(&SolverEvent::Contradiction { row: 1, col: 1 });
+
+        assert_eq!(stats.placements(Technique::HiddenSingle), 1);
+        assert_eq!(stats.placements(Technique::NakedSingle), 0);
+        assert_eq!(stats.total_placements(), 1);
+        assert_eq!(stats.deductions(Technique::Pointing), 1);
+        assert_eq!(stats.hardest_technique(), Some(Technique::Pointing));
+        assert_eq!(stats.eliminations(), 1);
+        assert_eq!(stats.guesses(), 3);
+        assert_eq!(stats.backtracks(), 1);
+        assert_eq!(stats.max_depth(), 2);
+        assert_eq!(stats.clones(), 3);
+        assert_eq!(
+            stats.to_string(),
+            "hidden single: 1, pointing: 1, eliminations: 1, guesses: 3, backtracks: 1, \
+             depth: 2, clones: 3, time: 0.000ms"
+        );
+    }
+
+    #[test]
+    fn nothing_to_report_for_nothing_done() {
+        let stats = SolveStats::new();
+        assert_eq!(stats.hardest_technique(), None);
+        assert_eq!(
+            stats.to_string(),
+            "eliminations: 0, guesses: 0, backtracks: 0, depth: 0, clones: 0, time: 0.000ms"
+        );
+    }
+
+    #[test]
+    fn solving_fills_in_the_stats() {
+        let grid = SudokuGrid::parse_str(ONE_SOLUTION).unwrap();
+        let (solved, stats) = BacktrackingSolver::new().solve_with_stats(&grid);
+        assert!(solved.unwrap().is_solved());
+        assert_eq!(stats.total_placements(), ONE_SOLUTION.matches('.').count());
+        assert_eq!(stats.guesses(), 0);
+        assert_eq!(stats.max_depth(), 0);
+        assert_eq!(stats.hardest_technique(), Some(Technique::HiddenSingle));
+
+        let grid = SudokuGrid::parse_str(HARD).unwrap();
+        let (solved, stats) = BacktrackingSolver::new().solve_with_stats(&grid);
+        assert!(solved.is_ok());
+        assert!(stats.guesses() > stats.backtracks());
+        assert!(stats.max_depth() > 0);
+        assert!(stats.clones() >= stats.guesses());
+    }
+}
@@ -0,0 +1,374 @@
+use super::sudoku_deduction::{positions, Candidate, Deduction, Pattern, Technique, Unit};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_units::UNITS;
+use super::sudoku_values::SudokuValues;
+
+impl SudokuGrid {
+    ///A naked subset is `size` cells of a unit whose possible values, between them, are only
+    ///`size` digits. Those cells have to take all of the digits, so no other cell of the unit
+    ///can hold any of them.
+    pub(crate) fn find_naked_subset(&self, size: usize) -> Option<Deduction> {
+        for (u, unit) in UNITS.iter().enumerate() {
+            //Cells with a single possible value are left to the naked singles
+            let open = unit
+                .iter()
+                .cloned()
+                .filter(|i| (2..=size).contains(&self.candidates_at(*i).len()))
+                .collect::<Vec<usize>>();
+            let sets = open
+                .iter()
+                .map(|i| self.candidates_at(*i))
+                .collect::<Vec<SudokuValues>>();
+
+            for picked in locked_subsets(&sets, size) {
+                let cells = picked.iter().map(|p| open[*p]).collect::<Vec<usize>>();
+                let digits = picked
+                    .iter()
+                    .fold(SudokuValues::empty(), |d, p| d.union(sets[*p]));
+
+                let mut eliminations = Vec::new();
+                for i in unit.iter().filter(|i| !cells.contains(i)) {
+                    for value in self.candidates_at(*i).intersection(digits) {
+                        eliminations.push(Candidate::at(*i, value));
+                    }
+                }
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: subset_technique(size, false),
+                        pattern: Pattern::Subset {
+                            unit: Unit::from_index(u),
+                            cells: positions(&cells),
+                            digits,
+                        },
+                        eliminations,
+                    });
+                }
+            }
+        }
+        return None;
+    }
+
+    ///A hidden subset is `size` digits that, between them, can only go in `size` cells of a
+    ///unit. Those cells have to take all of the digits, so they can't hold anything else.
+    pub(crate) fn find_hidden_subset(&self, size: usize) -> Option<Deduction> {
+        for (u, unit) in UNITS.iter().enumerate() {
+            //Where each digit can still go in the unit, with the cell at position p of the unit
+            //stored as the digit p + 1. Digits the unit already has, or that only have one
+            //place left, are left out.
+            let mut places = [SudokuValues::empty(); 10];
+            for (p, i) in unit.iter().enumerate() {
+                for value in self.candidates_at(*i) {
+                    places[value].insert(p + 1);
+                }
+            }
+            let open = (1..=9)
+                .filter(|d| (2..=size).contains(&places[*d].len()))
+                .collect::<Vec<usize>>();
+            let sets = open
+                .iter()
+                .map(|d| places[*d])
+                .collect::<Vec<SudokuValues>>();
+
+            for picked in locked_subsets(&sets, size) {
+                let mut digits = SudokuValues::empty();
+                let mut cell_places = SudokuValues::empty();
+                for p in picked.iter() {
+                    digits.insert(open[*p]);
+                    cell_places = cell_places.union(sets[*p]);
+                }
+                let cells = cell_places
+                    .iter()
+                    .map(|p| unit[p - 1])
+                    .collect::<Vec<usize>>();
+
+                let mut eliminations = Vec::new();
+                for i in cells.iter() {
+                    for value in self.candidates_at(*i).difference(digits) {
+                        eliminations.push(Candidate::at(*i, value));
+                    }
+                }
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: subset_technique(size, true),
+                        pattern: Pattern::Subset {
+                            unit: Unit::from_index(u),
+                            cells: positions(&cells),
+                            digits,
+                        },
+                        eliminations,
+                    });
+                }
+            }
+        }
+        return None;
+    }
+}
+
+///Every way of picking `size` of the sets so that, between them, they only hold `size` values.
+///Returns the positions of the sets picked.
+pub(crate) fn locked_subsets(sets: &[SudokuValues], size: usize) -> Vec<Vec<usize>> {
+    return subsets_within(sets, size, size);
+}
+
+///Every way of picking `size` of the sets so that, between them, they hold no more than
+///`max_values` values. Picks are abandoned as soon as they hold too many, so this stays quick
+///even though there are 126 ways of picking four cells of a unit.
+pub(crate) fn subsets_within(
+    sets: &[SudokuValues],
+    size: usize,
+    max_values: usize,
+) -> Vec<Vec<usize>> {
+    let mut found = Vec::new();
+    let mut picked = Vec::with_capacity(size);
+    pick_sets(
+        sets,
+        (size, max_values),
+        0,
+        SudokuValues::empty(),
+        &mut picked,
+        &mut found,
+    );
+    return found;
+}
+
+fn pick_sets(
+    sets: &[SudokuValues],
+    (size, max_values): (usize, usize),
+    start: usize,
+    values: SudokuValues,
+    picked: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+) {
+    if picked.len() == size {
+        found.push(picked.clone());
+        return;
+    }
+    for p in start..sets.len() {
+        let values = values.union(sets[p]);
+        if values.len() > max_values {
+            continue;
+        }
+        picked.push(p);
+        pick_sets(sets, (size, max_values), p + 1, values, picked, found);
+        picked.pop();
+    }
+}
+
+fn subset_technique(size: usize, hidden: bool) -> Technique {
+    return match (size, hidden) {
+        (2, false) => Technique::NakedPair,
+        (2, true) => Technique::HiddenPair,
+        (3, false) => Technique::NakedTriple,
+        (3, true) => Technique::HiddenTriple,
+        (_, false) => Technique::NakedQuad,
+        (_, true) => Technique::HiddenQuad,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naked_pair() {
+        //Partway through solving
+        //.31.5....8....9..66.5.1.3..2......9115...62......9....5...81..........5...6.4.8.2
+        let grid = SudokuGrid::from_candidates(
+            "
+            9 3  1  6  5  48 47  2   78
+            8 7  4  3  2  9  5   1   6
+            6 2  5  48 1  7  3   48  9
+            2 68 37 5  37 48 467 9   1
+            1 5  9  48 37 6  2   348 78
+            4 68 37 1  9  2  67  38  5
+            5 4  2  7  8  1  9   6   3
+            7 9  8  2  6  3  1   5   4
+            3 1  6  9  4  5  8   7   2
+            ",
+        );
+        assert_eq!(
+            grid.find_naked_subset(2),
+            Some(Deduction {
+                technique: Technique::NakedPair,
+                pattern: Pattern::Subset {
+                    unit: Unit::Row(3),
+                    cells: vec![(3, 2), (3, 4)],
+                    digits: SudokuValues::from_digits(&[3, 7]),
+                },
+                eliminations: vec![Candidate::new(3, 6, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn hidden_pair() {
+        //Partway through solving
+        //152....6.3.........4912........4..73...53.9...7....81.....1..3.......6...6...5..4
+        let grid = SudokuGrid::from_candidates(
+            "
+            1   5 2   79   789 3   4 6 78
+            3   8 67  467  5   46  1 9 2
+            67  4 9   1    2   68  3 5 78
+            56  9 56  8    4   1   2 7 3
+            2   1 8   5    3   7   9 4 6
+            4   7 3   29   6   29  8 1 5
+            78  2 47  467  1   468 5 3 9
+            579 3 457 2479 79  249 6 8 1
+            89  6 1   3    89  5   7 2 4
+            ",
+        );
+        assert_eq!(
+            grid.find_hidden_subset(2),
+            Some(Deduction {
+                technique: Technique::HiddenPair,
+                pattern: Pattern::Subset {
+                    unit: Unit::Column(5),
+                    cells: vec![(5, 5), (7, 5)],
+                    digits: SudokuValues::from_digits(&[2, 9]),
+                },
+                eliminations: vec![Candidate::new(7, 5, 4)],
+            })
+        );
+    }
+
+    #[test]
+    fn naked_triple() {
+        //Partway through solving
+        //.73.........1.5...8.5..9.2.4.......9..65..7.131..4.......7...........26.7...82.3.
+        let grid = SudokuGrid::from_candidates(
+            "
+            1  7   3  4  2   8  5 9 6
+            69 29  24 1  36  5  8 7 34
+            8  46  5  36 7   9  1 2 34
+            4  5   7  2  16  16 3 8 9
+            2  8   6  5  9   3  7 4 1
+            3  1   9  8  4   7  6 5 2
+            56 23  24 7  356 46 9 1 8
+            59 349 8  39 135 14 2 6 7
+            7  69  1  69 8   2  4 3 5
+            ",
+        );
+        assert_eq!(
+            grid.find_naked_subset(3),
+            Some(Deduction {
+                technique: Technique::NakedTriple,
+                pattern: Pattern::Subset {
+                    unit: Unit::Subgrid(6),
+                    cells: vec![(6, 0), (7, 0), (8, 1)],
+                    digits: SudokuValues::from_digits(&[5, 6, 9]),
+                },
+                eliminations: vec![Candidate::new(7, 1, 9)],
+            })
+        );
+    }
+
+    #[test]
+    fn hidden_triple() {
+        //Partway through solving
+        //.........5...3.14.4.6.8...9..85...34.4.3......9.7.42........9.2....26..3..9..7...
+        let grid = SudokuGrid::from_candidates(
+            "
+            9    38   13  4  7  15 568  2    568
+            5    278  27  6  3  9  1    4    78
+            4    17   6   2  8  15 3    57   9
+            167  167  8   5  9  2  67   3    4
+            267  4    257 3  16 8  567  9    1567
+            36   9    35  7  16 4  2    1568 1568
+            1678 1567 147 18 45 3  9    1578 2
+            178  157  147 9  2  6  4578 1578 3
+            23   23   9   18 45 7  4568 1568 1568
+            ",
+        );
+        assert_eq!(
+            grid.find_hidden_subset(3),
+            Some(Deduction {
+                technique: Technique::HiddenTriple,
+                pattern: Pattern::Subset {
+                    unit: Unit::Column(1),
+                    cells: vec![(0, 1), (1, 1), (8, 1)],
+                    digits: SudokuValues::from_digits(&[2, 3, 8]),
+                },
+                eliminations: vec![Candidate::new(1, 1, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn naked_quad() {
+        //Partway through solving
+        //62.7.4..58....1...43.58.............7...5...22.....7315.....3.9...6.31...4.....67
+        let grid = SudokuGrid::from_candidates(
+            "
+            6  2    19    7    3    4   89 18  5
+            8  579  579   29   6    1   4  27  3
+            4  3    17    5    8    29  29 17  6
+            13 1689 34689 123  127  27  5  489 48
+            7  189  3489  13   5    89  6  489 2
+            2  589  589   489  49   6   7  3   1
+            5  1678 2678  1248 1247 278 3  248 9
+            9  78   278   6    247  3   1  5   48
+            13 4    238   1289 129  5   28 6   7
+            ",
+        );
+        assert_eq!(
+            grid.find_naked_subset(4),
+            Some(Deduction {
+                technique: Technique::NakedQuad,
+                pattern: Pattern::Subset {
+                    unit: Unit::Row(3),
+                    cells: vec![(3, 0), (3, 3), (3, 4), (3, 5)],
+                    digits: SudokuValues::from_digits(&[1, 2, 3, 7]),
+                },
+                eliminations: vec![Candidate::new(3, 1, 1), Candidate::new(3, 2, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn hidden_quad() {
+        //Partway through solving
+        //13...7.......38.......2....5......9.....6.58..4.7.53.....29...6.12...75...4...1..
+        let grid = SudokuGrid::from_candidates(
+            "
+            1     3     689   469  5   7    24689 246   248
+            24679 25679 5679  1469 3   8    469   1467  145
+            46789 56789 56789 1469 2   469  4689  13467 13458
+            5     2678  13678 348  148 234  246   9     1247
+            237   27    137   349  6   2349 5     8     1247
+            2689  4     1689  7    18  5    3     126   12
+            378   578   3578  2    9   1    48    34    6
+            369   1     2     3468 48  346  7     5     39
+            3689  689   4     5    7   36   1     23    2389
+            ",
+        );
+        assert_eq!(
+            grid.find_hidden_subset(4),
+            Some(Deduction {
+                technique: Technique::HiddenQuad,
+                pattern: Pattern::Subset {
+                    unit: Unit::Subgrid(2),
+                    cells: vec![(1, 7), (1, 8), (2, 7), (2, 8)],
+                    digits: SudokuValues::from_digits(&[1, 3, 5, 7]),
+                },
+                eliminations: vec![
+                    Candidate::new(1, 7, 4),
+                    Candidate::new(1, 7, 6),
+                    Candidate::new(1, 8, 4),
+                    Candidate::new(2, 7, 4),
+                    Candidate::new(2, 7, 6),
+                    Candidate::new(2, 8, 4),
+                    Candidate::new(2, 8, 8),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn nothing_without_a_subset() {
+        let grid = SudokuGrid::new();
+        for size in 2..=4 {
+            assert_eq!(grid.find_naked_subset(size), None);
+            assert_eq!(grid.find_hidden_subset(size), None);
+        }
+    }
+}
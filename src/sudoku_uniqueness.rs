@@ -0,0 +1,666 @@
+use super::sudoku_deduction::{positions, Candidate, Deduction, Pattern, Technique};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_subsets::subsets_within;
+use super::sudoku_units::{col_of, row_of, CELL_UNITS, COL_UNIT, UNITS};
+use super::sudoku_values::SudokuValues;
+
+//These techniques only hold for puzzles with exactly one solution. Four cells at the corners of a
+//rectangle, across two subgrids, that all end up as either a or b form a "deadly pattern": the
+//a's and b's could be swapped, and the puzzle would have a second solution. So if the puzzle
+//has only one solution, anything that would leave the corners as a deadly pattern is ruled out.
+//Corners that are given can't be swapped, but cells without a value were never given.
+
+///Four cells at the corners of a rectangle across two subgrids, going round the rectangle, so
+///that corners next to each other in the list share a row or column and corners two apart are
+///opposite. Every corner can still be either of the digits.
+struct Rectangle {
+    corners: [usize; 4],
+    digits: SudokuValues,
+}
+
+impl Rectangle {
+    ///The corners that can be more than the two digits
+    fn roofs(&self, grid: &SudokuGrid) -> Vec<usize> {
+        return (0..4)
+            .filter(|c| grid.candidates_at(self.corners[*c]).len() > 2)
+            .collect();
+    }
+
+    ///The possible values of the corners, other than the two digits
+    fn extras(&self, grid: &SudokuGrid, corners: &[usize]) -> SudokuValues {
+        return corners.iter().fold(SudokuValues::empty(), |e, c| {
+            e.union(grid.candidates_at(self.corners[*c]).difference(self.digits))
+        });
+    }
+
+    fn cells(&self, corners: &[usize]) -> Vec<usize> {
+        let mut cells = corners
+            .iter()
+            .map(|c| self.corners[*c])
+            .collect::<Vec<usize>>();
+        cells.sort_unstable();
+        return cells;
+    }
+
+    fn deduction(&self, technique: Technique, eliminations: Vec<Candidate>) -> Option<Deduction> {
+        if eliminations.is_empty() {
+            return None;
+        }
+        return Some(Deduction {
+            technique,
+            pattern: Pattern::UniqueRectangle {
+                corners: positions(&self.corners),
+                digits: self.digits,
+            },
+            eliminations,
+        });
+    }
+}
+
+impl SudokuGrid {
+    ///Type 1: three corners can only be a or b, so the fourth can't be either
+    pub(crate) fn find_unique_rectangle_type_1(&self) -> Option<Deduction> {
+        for rectangle in self.rectangles() {
+            let roofs = rectangle.roofs(self);
+            if roofs.len() != 1 {
+                continue;
+            }
+            let roof = rectangle.corners[roofs[0]];
+            let eliminations = rectangle
+                .digits
+                .iter()
+                .map(|d| Candidate::at(roof, d))
+                .collect();
+            let deduction = rectangle.deduction(Technique::UniqueRectangleType1, eliminations);
+            if deduction.is_some() {
+                return deduction;
+            }
+        }
+        return None;
+    }
+
+    ///Type 2: two corners next to each other can only be a, b or c, and the other two only a or
+    ///b. One of the two has to be c, so no cell that sees both of them can be.
+    pub(crate) fn find_unique_rectangle_type_2(&self) -> Option<Deduction> {
+        return self.find_one_extra(Technique::UniqueRectangleType2, |r| {
+            r.len() == 2 && (r[1] - r[0]) % 2 == 1
+        });
+    }
+
+    ///Type 3: two corners next to each other can be more than a or b, and the other two can't.
+    ///The extra values of the two act like a single cell that has to be one of them, which can
+    ///form a naked subset with other cells of a unit they share.
+    pub(crate) fn find_unique_rectangle_type_3(&self) -> Option<Deduction> {
+        for rectangle in self.rectangles() {
+            let roofs = rectangle.roofs(self);
+            if roofs.len() != 2 || (roofs[1] - roofs[0]) % 2 == 0 {
+                continue;
+            }
+            let extras = rectangle.extras(self, &roofs);
+            let cells = rectangle.cells(&roofs);
+
+            for unit in shared_units(cells[0], cells[1]) {
+                let open = UNITS[unit]
+                    .iter()
+                    .cloned()
+                    .filter(|i| !cells.contains(i) && !self.candidates_at(*i).is_empty())
+                    .collect::<Vec<usize>>();
+                let sets = open
+                    .iter()
+                    .map(|i| self.candidates_at(*i))
+                    .collect::<Vec<SudokuValues>>();
+
+                for size in 1..open.len().min(4) {
+                    for picked in subsets_within(&sets, size, size + 1) {
+                        let digits = picked.iter().fold(extras, |d, p| d.union(sets[*p]));
+                        if digits.len() != size + 1 {
+                            continue;
+                        }
+                        let mut eliminations = Vec::new();
+                        for (p, i) in open.iter().enumerate() {
+                            if !picked.contains(&p) {
+                                for value in sets[p].intersection(digits) {
+                                    eliminations.push(Candidate::at(*i, value));
+                                }
+                            }
+                        }
+                        let deduction =
+                            rectangle.deduction(Technique::UniqueRectangleType3, eliminations);
+                        if deduction.is_some() {
+                            return deduction;
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Type 4: two corners next to each other can be more than a or b, and the other two can't.
+    ///If a unit the two share only has places for a in them, one of them is a, so neither can be
+    ///b.
+    pub(crate) fn find_unique_rectangle_type_4(&self) -> Option<Deduction> {
+        for rectangle in self.rectangles() {
+            let roofs = rectangle.roofs(self);
+            if roofs.len() != 2 || (roofs[1] - roofs[0]) % 2 == 0 {
+                continue;
+            }
+            let cells = rectangle.cells(&roofs);
+
+            for unit in shared_units(cells[0], cells[1]) {
+                for digit in rectangle.digits.iter() {
+                    if self.places_in(unit, digit) != cells {
+                        continue;
+                    }
+                    let other = rectangle.digits.difference(SudokuValues::from_digit(digit));
+                    let eliminations = cells
+                        .iter()
+                        .flat_map(|i| other.iter().map(move |d| Candidate::at(*i, d)))
+                        .collect();
+                    let deduction =
+                        rectangle.deduction(Technique::UniqueRectangleType4, eliminations);
+                    if deduction.is_some() {
+                        return deduction;
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///Type 5: two opposite corners, or three corners, can only be a, b or c, and the rest only a
+    ///or b. One of them has to be c, so no cell that sees all of them can be.
+    pub(crate) fn find_unique_rectangle_type_5(&self) -> Option<Deduction> {
+        return self.find_one_extra(Technique::UniqueRectangleType5, |r| {
+            r.len() == 3 || (r.len() == 2 && (r[1] - r[0]) % 2 == 0)
+        });
+    }
+
+    ///Type 6: two opposite corners can only be a or b, and the other two can be more. If a only
+    ///has places at the corners in both rows of the rectangle, or in both of its columns, neither
+    ///of the other two can be a: whichever of them was would make the rest of the rectangle a
+    ///deadly pattern.
+    pub(crate) fn find_unique_rectangle_type_6(&self) -> Option<Deduction> {
+        for rectangle in self.rectangles() {
+            let roofs = rectangle.roofs(self);
+            if roofs.len() != 2 || (roofs[1] - roofs[0]) % 2 == 1 {
+                continue;
+            }
+            let cells = rectangle.cells(&roofs);
+            let rows = [row_of(cells[0]), row_of(cells[1])];
+            let cols = [COL_UNIT + col_of(cells[0]), COL_UNIT + col_of(cells[1])];
+
+            for digit in rectangle.digits.iter() {
+                let locked_in = |lines: &[usize; 2]| {
+                    lines.iter().all(|l| {
+                        self.places_in(*l, digit)
+                            .iter()
+                            .all(|i| rectangle.corners.contains(i))
+                    })
+                };
+                if !locked_in(&rows) && !locked_in(&cols) {
+                    continue;
+                }
+                let eliminations = cells.iter().map(|i| Candidate::at(*i, digit)).collect();
+                let deduction = rectangle.deduction(Technique::UniqueRectangleType6, eliminations);
+                if deduction.is_some() {
+                    return deduction;
+                }
+            }
+        }
+        return None;
+    }
+
+    ///A hidden unique rectangle has a corner that can only be a or b. If a only has places at the
+    ///corners in both the row and the column of the opposite corner, that corner can't be b: a
+    ///would then have to go in the other two corners, and b in the first one.
+    pub(crate) fn find_hidden_unique_rectangle(&self) -> Option<Deduction> {
+        for rectangle in self.rectangles() {
+            for floor in 0..4 {
+                if self.candidates_at(rectangle.corners[floor]).len() != 2 {
+                    continue;
+                }
+                let opposite = rectangle.corners[(floor + 2) % 4];
+                let lines = [row_of(opposite), COL_UNIT + col_of(opposite)];
+
+                for digit in rectangle.digits.iter() {
+                    let locked = lines.iter().all(|l| {
+                        self.places_in(*l, digit)
+                            .iter()
+                            .all(|i| rectangle.corners.contains(i))
+                    });
+                    if !locked {
+                        continue;
+                    }
+                    let other = rectangle.digits.difference(SudokuValues::from_digit(digit));
+                    let eliminations = other.iter().map(|d| Candidate::at(opposite, d)).collect();
+                    let deduction =
+                        rectangle.deduction(Technique::HiddenUniqueRectangle, eliminations);
+                    if deduction.is_some() {
+                        return deduction;
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///BUG+1: every empty cell can only be one of two values apart from one, which can be three,
+    ///and every digit has two places in each unit apart from the one digit with three in that
+    ///cell's units. Without that digit the grid would be a Bivalue Universal Grave, which always
+    ///has two solutions, so the cell has to be the digit.
+    pub(crate) fn find_bug_plus_one(&self) -> Option<Deduction> {
+        let mut extra = None;
+        for i in 0..81 {
+            match self.candidates_at(i).len() {
+                0 | 2 => {}
+                3 if extra.is_none() => extra = Some(i),
+                _ => return None,
+            }
+        }
+        let cell = extra?;
+
+        let mut digit = None;
+        for (u, unit) in UNITS.iter().enumerate() {
+            for d in 1..=9 {
+                let places = unit
+                    .iter()
+                    .filter(|i| self.candidates_at(**i).contains(d))
+                    .count();
+                match places {
+                    0 | 2 => {}
+                    3 if CELL_UNITS[cell].contains(&u) && digit.unwrap_or(d) == d => {
+                        digit = Some(d)
+                    }
+                    _ => return None,
+                }
+            }
+        }
+        let digit = digit.filter(|d| self.candidates_at(cell).contains(*d))?;
+
+        return Some(Deduction {
+            technique: Technique::BugPlusOne,
+            pattern: Pattern::Bug {
+                cell: (row_of(cell), col_of(cell)),
+                digit,
+            },
+            eliminations: self
+                .candidates_at(cell)
+                .iter()
+                .filter(|d| *d != digit)
+                .map(|d| Candidate::at(cell, d))
+                .collect(),
+        });
+    }
+
+    ///Types 2 and 5, where the corners that can be more than a or b can only be one more digit,
+    ///the same for all of them, and are placed the way `fits` wants
+    fn find_one_extra(
+        &self,
+        technique: Technique,
+        fits: fn(&[usize]) -> bool,
+    ) -> Option<Deduction> {
+        for rectangle in self.rectangles() {
+            let roofs = rectangle.roofs(self);
+            if !fits(&roofs) {
+                continue;
+            }
+            let extra = match rectangle.extras(self, &roofs).single() {
+                Some(e) => e,
+                None => continue,
+            };
+            let eliminations = self.seen_by_all(&rectangle.cells(&roofs), extra);
+            let deduction = rectangle.deduction(technique, eliminations);
+            if deduction.is_some() {
+                return deduction;
+            }
+        }
+        return None;
+    }
+
+    ///Every rectangle of empty cells across two subgrids, with each pair of digits all four
+    ///corners can be
+    fn rectangles(&self) -> Vec<Rectangle> {
+        let mut rectangles = Vec::new();
+        for top in 0..8 {
+            for bottom in top + 1..9 {
+                for left in 0..8 {
+                    for right in left + 1..9 {
+                        let corners = [
+                            top * 9 + left,
+                            top * 9 + right,
+                            bottom * 9 + right,
+                            bottom * 9 + left,
+                        ];
+                        //Either both rows are in one band, or both columns in one stack
+                        if (top / 3 == bottom / 3) == (left / 3 == right / 3) {
+                            continue;
+                        }
+                        let common = corners.iter().fold(SudokuValues::all(), |c, i| {
+                            c.intersection(self.candidates_at(*i))
+                        });
+                        let common = common.iter().collect::<Vec<usize>>();
+                        for (i, a) in common.iter().enumerate() {
+                            for b in common[i + 1..].iter() {
+                                let mut digits = SudokuValues::from_digit(*a);
+                                digits.insert(*b);
+                                rectangles.push(Rectangle { corners, digits });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return rectangles;
+    }
+}
+
+///The rows, columns and subgrids both cells are in
+fn shared_units(a: usize, b: usize) -> Vec<usize> {
+    return CELL_UNITS[a]
+        .iter()
+        .cloned()
+        .filter(|u| CELL_UNITS[b].contains(u))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_rectangle_type_1() {
+        //Partway through solving
+        //....7..2....1..8.6...5.4...5...6.1.....2....3.76.19....1.7...8.......2..82.3.5..7
+        let grid = SudokuGrid::from_candidates(
+            "
+            9 345 1  6 7  8 345 2    45
+            7 345 35 1 39 2 8   3459 6
+            2 6   8  5 39 4 7   39   1
+            5 9   2  4 6  3 1   7    8
+            1 8   4  2 5  7 9   6    3
+            3 7   6  8 1  9 45  45   2
+            4 1   35 7 2  6 35  8    9
+            6 35  7  9 8  1 2   345  45
+            8 2   9  3 4  5 6   1    7
+            ",
+        );
+        assert_eq!(
+            grid.find_unique_rectangle_type_1(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType1,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(1, 4), (1, 7), (2, 7), (2, 4)],
+                    digits: SudokuValues::from_digits(&[3, 9]),
+                },
+                eliminations: vec![Candidate::new(1, 7, 3), Candidate::new(1, 7, 9)],
+            })
+        );
+    }
+
+    #[test]
+    fn unique_rectangle_type_2() {
+        //Partway through solving
+        //.962..1......6324......83.7.2.4.........2.6...67......2.1...........1..8.4..9....
+        let grid = SudokuGrid::from_candidates(
+            "
+            3   9   6   2   7  4 1  8 5
+            7   158 58  15  6  3 2  4 9
+            4   15  2   9   15 8 3  6 7
+            9   2   35  4   15 6 8  7 13
+            158 358 4   158 2  7 6  9 13
+            18  6   7   18  3  9 4  5 2
+            2   7   1   6   8  5 9  3 4
+            6   35  9   37  4  1 57 2 8
+            58  4   358 37  9  2 57 1 6
+            ",
+        );
+        assert_eq!(
+            grid.find_unique_rectangle_type_2(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType2,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(4, 0), (4, 3), (5, 3), (5, 0)],
+                    digits: SudokuValues::from_digits(&[1, 8]),
+                },
+                eliminations: vec![Candidate::new(4, 1, 5)],
+            })
+        );
+    }
+
+    #[test]
+    fn unique_rectangle_type_3() {
+        //Partway through solving
+        //........2.2....48..16.2..7..8..7....3....5...6.5.....9..84...65...9......3.7.2.9.
+        let grid = SudokuGrid::from_candidates(
+            "
+            8 5  7 36  4 36  9   1   2
+            9 2  3 5   1 7   4   8   6
+            4 1  6 8   2 9   5   7   3
+            2 8  9 136 7 346 136 5   14
+            3 47 1 26  9 5   678 24  478
+            6 47 5 123 8 34  137 234 9
+            7 9  8 4   3 1   2   6   5
+            1 6  2 9   5 8   37  34  47
+            5 3  4 7   6 2   18  9   18
+            ",
+        );
+        assert_eq!(
+            grid.find_unique_rectangle_type_3(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType3,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(0, 3), (0, 5), (3, 5), (3, 3)],
+                    digits: SudokuValues::from_digits(&[3, 6]),
+                },
+                eliminations: vec![Candidate::new(3, 6, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn unique_rectangle_type_4() {
+        //Partway through solving
+        //..1........5291...48.3....2..3..8.1....14.82.......9...3..6..9..5.43.......9...6.
+        let grid = SudokuGrid::from_candidates(
+            "
+            6 2 1 57  8  4 57  3  9
+            3 7 5 2   9  1 6   4  8
+            4 8 9 3   57 6 1   57 2
+            2 9 3 567 57 8 457 1  467
+            5 6 7 1   4  9 8   2  3
+            8 1 4 567 2  3 9   57 67
+            1 3 2 8   6  5 47  9  47
+            9 5 6 4   3  7 2   8  1
+            7 4 8 9   1  2 3   6  5
+            ",
+        );
+        assert_eq!(
+            grid.find_unique_rectangle_type_4(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType4,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(3, 6), (3, 8), (6, 8), (6, 6)],
+                    digits: SudokuValues::from_digits(&[4, 7]),
+                },
+                eliminations: vec![Candidate::new(3, 6, 7), Candidate::new(3, 8, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn unique_rectangle_type_6() {
+        //Partway through solving
+        //1......9..49...25....78..1....5..9...961...48....4.52.4....7.....2.......8.3.2...
+        let grid = SudokuGrid::from_candidates(
+            "
+            1   36  7  2 5 4 8  9  36
+            8   4   9  6 3 1 2  5  7
+            236 25  35 7 8 9 36 1  4
+            23  123 4  5 7 8 9  36 136
+            5   9   6  1 2 3 7  4  8
+            37  137 8  9 4 6 5  2  13
+            4   56  35 8 9 7 1  36 2
+            367 37  2  4 1 5 36 8  9
+            9   8   1  3 6 2 4  7  5
+            ",
+        );
+        assert_eq!(
+            grid.find_unique_rectangle_type_6(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType6,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(5, 0), (5, 1), (7, 1), (7, 0)],
+                    digits: SudokuValues::from_digits(&[3, 7]),
+                },
+                eliminations: vec![Candidate::new(5, 1, 7), Candidate::new(7, 0, 7)],
+            })
+        );
+    }
+
+    #[test]
+    fn hidden_unique_rectangle() {
+        //Partway through solving
+        //.......3..1.8..2......54..94......27..5........8.27.1.5......71......4..7..4.29.6
+        let grid = SudokuGrid::from_candidates(
+            "
+            69 5  4  2   69  1   7   3 8
+            3  1  69 8   7   69  2   4 5
+            8  2  7  3   5   4   1   6 9
+            4  69 1  569 689 3   568 2 7
+            2  7  5  1   4   68  68  9 3
+            69 3  8  569 2   7   56  1 4
+            5  4  2  69  689 689 3   7 1
+            1  69 69 7   3   5   4   8 2
+            7  8  3  4   1   2   9   5 6
+            ",
+        );
+        assert_eq!(
+            grid.find_hidden_unique_rectangle(),
+            Some(Deduction {
+                technique: Technique::HiddenUniqueRectangle,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(3, 3), (3, 6), (5, 6), (5, 3)],
+                    digits: SudokuValues::from_digits(&[5, 6]),
+                },
+                eliminations: vec![Candidate::new(3, 3, 6)],
+            })
+        );
+    }
+
+    #[test]
+    fn unique_rectangle_type_5() {
+        //Three corners can also be 3, so one of them has to be. r2c2 and r3c2 see all three.
+        let grid = SudokuGrid::from_candidates(
+            "
+            123 123 . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            12  123 . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            ",
+        );
+        assert_eq!(
+            grid.find_unique_rectangle_type_5(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType5,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(0, 0), (0, 1), (3, 1), (3, 0)],
+                    digits: SudokuValues::from_digits(&[1, 2]),
+                },
+                eliminations: vec![Candidate::new(1, 1, 3), Candidate::new(2, 1, 3)],
+            })
+        );
+        assert_eq!(grid.find_unique_rectangle_type_2(), None);
+    }
+
+    ///A rectangle where r1c1 and r4c2 can only be 1 or 2, and r1c2 and r4c1 can be more. When
+    ///`locked` is true, 1 can only go in the corners of rows 1 and 4.
+    fn type_6_rectangle(locked: bool) -> SudokuGrid {
+        let mut grid = SudokuGrid::from_candidates(
+            "
+            12  123 . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            124 12  . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            .   .   . . . . . . .
+            ",
+        );
+        for col in 2..9 {
+            if !locked && col == 5 {
+                continue;
+            }
+            grid.remove_candidate(col, 1);
+            grid.remove_candidate(27 + col, 1);
+        }
+        return grid;
+    }
+
+    #[test]
+    fn unique_rectangle_type_6_needs_the_digit_locked() {
+        //If r1c2 were 1, r1c1 and r4c2 would be 2 and r4c1 would be 1, a deadly pattern. The
+        //same goes for r4c1.
+        assert_eq!(
+            type_6_rectangle(true).find_unique_rectangle_type_6(),
+            Some(Deduction {
+                technique: Technique::UniqueRectangleType6,
+                pattern: Pattern::UniqueRectangle {
+                    corners: vec![(0, 0), (0, 1), (3, 1), (3, 0)],
+                    digits: SudokuValues::from_digits(&[1, 2]),
+                },
+                eliminations: vec![Candidate::new(0, 1, 1), Candidate::new(3, 0, 1)],
+            })
+        );
+        //With r1c6 also able to be 1, row 1 could get its 1 there instead
+        assert_eq!(type_6_rectangle(false).find_unique_rectangle_type_6(), None);
+    }
+
+    //Partway through solving
+    //.3........2.1...69..67..1..7....23....4.958....8.........94372....2........68..1.
+    //Every empty cell can be two values, apart from r3c1
+    const BUG: &str = "
+        1   3  9 5 6 4 2  8 7
+        45  2  7 1 3 8 45 6 9
+        458 48 6 7 2 9 1  3 45
+        7   9  5 8 1 2 3  4 6
+        2   6  4 3 9 5 8  7 1
+        3   1  8 4 7 6 9  5 2
+        6   58 1 9 4 3 7  2 58
+        48  7  3 2 5 1 6  9 48
+        9   45 2 6 8 7 45 1 3
+        ";
+
+    #[test]
+    fn bug_plus_one() {
+        //4 has three places in row 3, column 1 and subgrid 1, so r3c1 has to be 4
+        assert_eq!(
+            SudokuGrid::from_candidates(BUG).find_bug_plus_one(),
+            Some(Deduction {
+                technique: Technique::BugPlusOne,
+                pattern: Pattern::Bug {
+                    cell: (2, 0),
+                    digit: 4,
+                },
+                eliminations: vec![Candidate::new(2, 0, 5), Candidate::new(2, 0, 8)],
+            })
+        );
+    }
+
+    #[test]
+    fn bug_plus_one_needs_exactly_one_cell_of_three_values() {
+        //r3c2 can also be 5, so there are two cells of three values
+        let two = BUG.replacen("458 48 6", "458 458 6", 1);
+        assert_eq!(SudokuGrid::from_candidates(&two).find_bug_plus_one(), None);
+        //r3c1 can't be 5, so every cell can be two values
+        let none = BUG.replacen("458 48 6", "48 48 6", 1);
+        assert_eq!(SudokuGrid::from_candidates(&none).find_bug_plus_one(), None);
+    }
+}
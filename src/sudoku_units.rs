@@ -0,0 +1,158 @@
+//Lookup tables describing which cells belong together.
+//Cells are indexed 0 to 80 in reading order. Units are the 27 groups of 9 cells that must
+//each hold every digit once: rows are units 0 to 8, columns 9 to 17 and subgrids 18 to 26.
+//A cell's peers are the 20 other cells that share a unit with it.
+//Everything here is worked out at compile time, so the solver never has to scan the whole
+//grid to find the cells related to another.
+
+pub(crate) const ROW_UNIT: usize = 0;
+pub(crate) const COL_UNIT: usize = 9;
+pub(crate) const SUBGRID_UNIT: usize = 18;
+
+///The cells of each unit
+pub(crate) const UNITS: [[usize; 9]; 27] = build_units();
+
+///The row, column and subgrid unit of each cell
+pub(crate) const CELL_UNITS: [[usize; 3]; 81] = build_cell_units();
+
+///The cells that share a row, column or subgrid with each cell
+pub(crate) const PEERS: [[usize; 20]; 81] = build_peers();
+
+///A set of cells, with bit i set for cell i
+pub(crate) type CellMask = u128;
+
+///The peers of each cell, as a mask
+pub(crate) const PEER_MASKS: [CellMask; 81] = build_peer_masks();
+
+pub(crate) const fn row_of(index: usize) -> usize {
+    return index / 9;
+}
+
+pub(crate) const fn col_of(index: usize) -> usize {
+    return index % 9;
+}
+
+pub(crate) const fn subgrid_of(index: usize) -> usize {
+    return (index / 27) * 3 + (index % 9) / 3;
+}
+
+///Returns true if two different cells share a row, column or subgrid
+pub(crate) const fn sees(a: usize, b: usize) -> bool {
+    return a != b
+        && (row_of(a) == row_of(b) || col_of(a) == col_of(b) || subgrid_of(a) == subgrid_of(b));
+}
+
+pub(crate) const fn cell_bit(index: usize) -> CellMask {
+    return 1 << index;
+}
+
+const fn build_units() -> [[usize; 9]; 27] {
+    let mut units = [[0; 9]; 27];
+    let mut i = 0;
+    while i < 9 {
+        let mut j = 0;
+        while j < 9 {
+            units[ROW_UNIT + i][j] = i * 9 + j;
+            units[COL_UNIT + i][j] = j * 9 + i;
+            units[SUBGRID_UNIT + i][j] = (i / 3) * 27 + (i % 3) * 3 + (j / 3) * 9 + j % 3;
+            j += 1;
+        }
+        i += 1;
+    }
+    return units;
+}
+
+const fn build_cell_units() -> [[usize; 3]; 81] {
+    let mut cell_units = [[0; 3]; 81];
+    let mut i = 0;
+    while i < 81 {
+        cell_units[i] = [
+            ROW_UNIT + row_of(i),
+            COL_UNIT + col_of(i),
+            SUBGRID_UNIT + subgrid_of(i),
+        ];
+        i += 1;
+    }
+    return cell_units;
+}
+
+const fn build_peers() -> [[usize; 20]; 81] {
+    let mut peers = [[0; 20]; 81];
+    let mut i = 0;
+    while i < 81 {
+        let mut count = 0;
+        let mut j = 0;
+        while j < 81 {
+            if sees(i, j) {
+                peers[i][count] = j;
+                count += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    return peers;
+}
+
+const fn build_peer_masks() -> [CellMask; 81] {
+    let mut masks = [0; 81];
+    let mut i = 0;
+    while i < 81 {
+        let mut j = 0;
+        while j < 81 {
+            if sees(i, j) {
+                masks[i] |= cell_bit(j);
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    return masks;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_cover_the_grid() {
+        for unit in UNITS.iter() {
+            let mask = unit.iter().fold(0, |mask, &i| mask | cell_bit(i));
+            assert_eq!(mask.count_ones(), 9);
+        }
+        assert_eq!(UNITS[ROW_UNIT + 1], [9, 10, 11, 12, 13, 14, 15, 16, 17]);
+        assert_eq!(UNITS[COL_UNIT + 2], [2, 11, 20, 29, 38, 47, 56, 65, 74]);
+        assert_eq!(
+            UNITS[SUBGRID_UNIT + 4],
+            [30, 31, 32, 39, 40, 41, 48, 49, 50]
+        );
+        for (i, units) in CELL_UNITS.iter().enumerate() {
+            for &unit in units.iter() {
+                assert!(UNITS[unit].contains(&i));
+            }
+        }
+    }
+
+    #[test]
+    fn every_cell_has_20_peers() {
+        for i in 0..81 {
+            let mask = PEERS[i].iter().fold(0, |mask, &j| mask | cell_bit(j));
+            assert_eq!(mask.count_ones(), 20);
+            assert_eq!(mask, PEER_MASKS[i]);
+            assert_eq!(PEER_MASKS[i] & cell_bit(i), 0);
+            for &j in PEERS[i].iter() {
+                assert!(PEERS[j].contains(&i));
+            }
+        }
+    }
+
+    #[test]
+    fn peers_share_a_unit() {
+        //r5c5 and r6c6 share a subgrid, r5c5 and r5c1 a row, r5c5 and r1c5 a column
+        assert!(sees(40, 50) && sees(40, 36) && sees(40, 4));
+        assert!(!sees(40, 40));
+        assert!(!sees(40, 0));
+        assert_eq!(subgrid_of(80), 8);
+        assert_eq!(subgrid_of(33), 5);
+    }
+}
@@ -1,32 +1,165 @@
-use core::ops::Index;
-use core::ops::IndexMut;
 use std::fmt;
 
-///Purpose of this struct
-#[derive(Clone)]
+const ALL_DIGITS: u16 = 0b1_1111_1111;
+
+///A set of sudoku digits. For a cell these are the values that are still possible,
+///for a row/column/subgrid these are the values that are already present.
+///Stored as a bitmask, where bit 0 corresponds to the digit 1 and bit 8 to the digit 9, so
+///every operation is a handful of instructions rather than a loop over the digits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SudokuValues {
-    pub values: [bool; 9],
+    bits: u16,
 }
 
 impl SudokuValues {
+    ///Every digit if `init_val` is true, no digits otherwise
     pub fn new(init_val: bool) -> Self {
+        return if init_val {
+            SudokuValues::all()
+        } else {
+            SudokuValues::empty()
+        };
+    }
+
+    pub fn empty() -> Self {
+        SudokuValues { bits: 0 }
+    }
+
+    pub fn all() -> Self {
+        SudokuValues { bits: ALL_DIGITS }
+    }
+
+    ///A set holding just the given digit.
+    ///Panics if the digit isn't 1 to 9, since it would land outside the set's nine bits.
+    pub fn from_digit(digit: usize) -> Self {
+        assert!(
+            (1..=9).contains(&digit),
+            "{} is not a sudoku digit, which has to be 1 to 9",
+            digit
+        );
+        SudokuValues {
+            bits: 1 << (digit - 1),
+        }
+    }
+
+    ///Returns true if the given digit (1 to 9) is in the set
+    pub fn contains(&self, digit: usize) -> bool {
+        return (1..=9).contains(&digit) && self.bits & (1 << (digit - 1)) != 0;
+    }
+
+    ///Adds the digit to the set. Panics if it isn't 1 to 9, like `from_digit`.
+    pub fn insert(&mut self, digit: usize) {
+        self.bits |= SudokuValues::from_digit(digit).bits;
+    }
+
+    ///Takes the digit out of the set. Panics if it isn't 1 to 9, like `from_digit`.
+    pub fn remove(&mut self, digit: usize) {
+        self.bits &= !SudokuValues::from_digit(digit).bits;
+    }
+
+    ///The number of digits in the set
+    pub fn len(&self) -> usize {
+        return self.bits.count_ones() as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.bits == 0;
+    }
+
+    ///The digits in both sets
+    pub fn intersection(&self, other: SudokuValues) -> SudokuValues {
         SudokuValues {
-            values: [init_val; 9],
+            bits: self.bits & other.bits,
+        }
+    }
+
+    ///The digits in either set
+    pub fn union(&self, other: SudokuValues) -> SudokuValues {
+        SudokuValues {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    ///The digits in this set that aren't in `other`
+    pub fn difference(&self, other: SudokuValues) -> SudokuValues {
+        SudokuValues {
+            bits: self.bits & !other.bits,
+        }
+    }
+
+    ///The digits that aren't in this set
+    pub fn complement(&self) -> SudokuValues {
+        SudokuValues {
+            bits: !self.bits & ALL_DIGITS,
+        }
+    }
+
+    ///The only digit in the set, if it holds exactly one
+    pub fn single(&self) -> Option<usize> {
+        return if self.len() == 1 {
+            Some(self.bits.trailing_zeros() as usize + 1)
+        } else {
+            None
+        };
+    }
+
+    ///The smallest digit in the set
+    pub fn first(&self) -> Option<usize> {
+        return if self.is_empty() {
+            None
+        } else {
+            Some(self.bits.trailing_zeros() as usize + 1)
+        };
+    }
+
+    ///Iterates over the digits in the set, in ascending order
+    pub fn iter(&self) -> SudokuValuesIter {
+        SudokuValuesIter { bits: self.bits }
+    }
+}
+
+#[cfg(test)]
+impl SudokuValues {
+    pub(crate) fn from_digits(digits: &[usize]) -> Self {
+        let mut values = SudokuValues::empty();
+        for digit in digits {
+            values.insert(*digit);
         }
+        return values;
+    }
+}
+
+impl Default for SudokuValues {
+    fn default() -> Self {
+        return SudokuValues::empty();
     }
 }
 
-impl Index<usize> for SudokuValues {
-    type Output = bool;
+impl IntoIterator for SudokuValues {
+    type Item = usize;
+    type IntoIter = SudokuValuesIter;
 
-    fn index(&self, index: usize) -> &bool {
-        &self.values[index]
+    fn into_iter(self) -> SudokuValuesIter {
+        return self.iter();
     }
 }
 
-impl IndexMut<usize> for SudokuValues {
-    fn index_mut(&mut self, index: usize) -> &mut bool {
-        &mut self.values[index]
+///The digits of a `SudokuValues`, smallest first
+pub struct SudokuValuesIter {
+    bits: u16,
+}
+
+impl Iterator for SudokuValuesIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.bits == 0 {
+            return None;
+        }
+        let digit = self.bits.trailing_zeros() as usize + 1;
+        //Clear the lowest set bit
+        self.bits &= self.bits - 1;
+        return Some(digit);
     }
 }
 
@@ -34,11 +167,9 @@ impl fmt::Debug for SudokuValues {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut pos_val_string = String::new();
         pos_val_string.push('[');
-        for i in 0..9 {
-            if self.values[i] {
-                pos_val_string.push_str(&(i + 1).to_string());
-                pos_val_string.push_str(", ");
-            }
+        for d in self.iter() {
+            pos_val_string.push_str(&d.to_string());
+            pos_val_string.push_str(", ");
         }
         pos_val_string.push(']');
 
@@ -50,14 +181,84 @@ impl fmt::Display for SudokuValues {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut pos_val_string = String::new();
         pos_val_string.push('[');
-        for i in 0..9 {
-            if self.values[i] {
-                pos_val_string.push_str(&(i + 1).to_string());
-                pos_val_string.push_str(", ");
-            }
+        for d in self.iter() {
+            pos_val_string.push_str(&d.to_string());
+            pos_val_string.push_str(", ");
         }
         pos_val_string.push(']');
 
         return write!(f, "Cell Data: {}", pos_val_string);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(digits: &[usize]) -> SudokuValues {
+        let mut values = SudokuValues::empty();
+        for &d in digits {
+            values.insert(d);
+        }
+        return values;
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = SudokuValues::empty();
+        assert!(set.is_empty());
+        set.insert(1);
+        set.insert(9);
+        set.insert(9);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(1) && set.contains(9) && !set.contains(5));
+        set.remove(1);
+        set.remove(4);
+        assert_eq!(set, SudokuValues::from_digit(9));
+        assert_eq!(SudokuValues::all().len(), 9);
+        assert_eq!(SudokuValues::new(true), SudokuValues::all());
+        assert_eq!(SudokuValues::new(false), SudokuValues::default());
+    }
+
+    #[test]
+    fn nothing_outside_1_to_9_is_contained() {
+        assert!(!SudokuValues::all().contains(0));
+        assert!(!SudokuValues::all().contains(10));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = values(&[1, 2, 3, 4]);
+        let b = values(&[3, 4, 5]);
+        assert_eq!(a.intersection(b), values(&[3, 4]));
+        assert_eq!(a.union(b), values(&[1, 2, 3, 4, 5]));
+        assert_eq!(a.difference(b), values(&[1, 2]));
+        assert_eq!(a.complement(), values(&[5, 6, 7, 8, 9]));
+        assert_eq!(SudokuValues::all().complement(), SudokuValues::empty());
+    }
+
+    #[test]
+    fn digits_come_out_smallest_first() {
+        let set = values(&[7, 2, 9, 4]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![2, 4, 7, 9]);
+        assert_eq!(set.into_iter().count(), 4);
+        assert_eq!(set.first(), Some(2));
+        assert_eq!(set.single(), None);
+        assert_eq!(values(&[6]).single(), Some(6));
+        assert_eq!(SudokuValues::empty().first(), None);
+        assert_eq!(SudokuValues::empty().iter().next(), None);
+        assert_eq!(format!("{}", values(&[1, 5])), "Cell Data: [1, 5, ]");
+    }
+
+    #[test]
+    #[should_panic(expected = "0 is not a sudoku digit")]
+    fn zero_is_not_a_digit() {
+        SudokuValues::empty().insert(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "10 is not a sudoku digit")]
+    fn ten_is_not_a_digit() {
+        SudokuValues::all().remove(10);
+    }
+}
@@ -0,0 +1,289 @@
+use super::sudoku_deduction::{positions, Deduction, Pattern, Technique};
+use super::sudoku_grid::SudokuGrid;
+use super::sudoku_units::{sees, PEERS, UNITS};
+
+impl SudokuGrid {
+    ///An XY-Wing is a pivot cell that can only be x or y, seeing two pincer cells that can only
+    ///be x or z and y or z. Whichever value the pivot takes, one of the pincers has to be z, so
+    ///no cell that sees both pincers can be z.
+    pub(crate) fn find_xy_wing(&self) -> Option<Deduction> {
+        for pivot in 0..81 {
+            let pivot_digits = self.candidates_at(pivot);
+            if pivot_digits.len() != 2 {
+                continue;
+            }
+            //Pincers share exactly one digit with the pivot
+            let pincers = self.wing_pincers(pivot, |p| {
+                let digits = self.candidates_at(p);
+                digits.len() == 2 && digits.intersection(pivot_digits).len() == 1
+            });
+
+            for (i, first) in pincers.iter().enumerate() {
+                for second in pincers[i + 1..].iter() {
+                    let first_digits = self.candidates_at(*first);
+                    let second_digits = self.candidates_at(*second);
+                    let z = first_digits
+                        .intersection(second_digits)
+                        .difference(pivot_digits);
+                    //The pincers have to hold different digits of the pivot
+                    if first_digits.intersection(second_digits).len() != 1 || z.len() != 1 {
+                        continue;
+                    }
+                    let digit = z.first().unwrap_or(0);
+                    let deduction =
+                        self.wing(Technique::XYWing, &[pivot], &[*first, *second], digit);
+                    if deduction.is_some() {
+                        return deduction;
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///An XYZ-Wing is an XY-Wing where the pivot can also be z. Now the pivot might be z itself,
+    ///so only cells that see the pivot as well as both pincers can't be z.
+    pub(crate) fn find_xyz_wing(&self) -> Option<Deduction> {
+        for pivot in 0..81 {
+            let pivot_digits = self.candidates_at(pivot);
+            if pivot_digits.len() != 3 {
+                continue;
+            }
+            let pincers = self.wing_pincers(pivot, |p| {
+                let digits = self.candidates_at(p);
+                digits.len() == 2 && digits.difference(pivot_digits).is_empty()
+            });
+
+            for (i, first) in pincers.iter().enumerate() {
+                for second in pincers[i + 1..].iter() {
+                    let z = self
+                        .candidates_at(*first)
+                        .intersection(self.candidates_at(*second));
+                    if z.len() != 1 {
+                        continue;
+                    }
+                    let digit = z.first().unwrap_or(0);
+                    let deduction =
+                        self.wing(Technique::XYZWing, &[pivot], &[*first, *second], digit);
+                    if deduction.is_some() {
+                        return deduction;
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///A W-Wing is two cells that can only be x or z, which don't see each other, joined by a
+    ///strong link on x: a unit where x only has two places, one seeing each of the cells.
+    ///If neither cell were z, both would be x, leaving x nowhere to go in the linking unit. So
+    ///one of them is z, and no cell that sees both can be.
+    ///The strong link is reported as the pivot.
+    pub(crate) fn find_w_wing(&self) -> Option<Deduction> {
+        for first in 0..81 {
+            let digits = self.candidates_at(first);
+            if digits.len() != 2 {
+                continue;
+            }
+            for second in first + 1..81 {
+                if self.candidates_at(second) != digits || sees(first, second) {
+                    continue;
+                }
+                for x in digits {
+                    let z = digits.iter().find(|d| *d != x).unwrap_or(0);
+                    for unit in 0..UNITS.len() {
+                        let places = self.places_in(unit, x);
+                        if places.len() != 2 || places.contains(&first) || places.contains(&second)
+                        {
+                            continue;
+                        }
+                        let linked = (sees(places[0], first) && sees(places[1], second))
+                            || (sees(places[1], first) && sees(places[0], second));
+                        if !linked {
+                            continue;
+                        }
+                        let deduction = self.wing(Technique::WWing, &places, &[first, second], z);
+                        if deduction.is_some() {
+                            return deduction;
+                        }
+                    }
+                }
+            }
+        }
+        return None;
+    }
+
+    ///The unsolved peers of the pivot that pass the test
+    fn wing_pincers<F: Fn(usize) -> bool>(&self, pivot: usize, is_pincer: F) -> Vec<usize> {
+        return PEERS[pivot]
+            .iter()
+            .cloned()
+            .filter(|p| is_pincer(*p))
+            .collect();
+    }
+
+    ///Rules `digit` out of every cell that sees all of the pincers, and for an XYZ-Wing the
+    ///pivot as well, since that can be the digit too
+    fn wing(
+        &self,
+        technique: Technique,
+        pivot: &[usize],
+        pincers: &[usize],
+        digit: usize,
+    ) -> Option<Deduction> {
+        let mut cells = pincers.to_vec();
+        if technique == Technique::XYZWing {
+            cells.extend_from_slice(pivot);
+        }
+        let eliminations = self.seen_by_all(&cells, digit);
+        if eliminations.is_empty() {
+            return None;
+        }
+        return Some(Deduction {
+            technique,
+            pattern: Pattern::Wing {
+                pivot: positions(pivot),
+                pincers: positions(pincers),
+                digit,
+            },
+            eliminations,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku_deduction::Candidate;
+
+    #[test]
+    fn xy_wing() {
+        //Partway through solving
+        //.2.89.....3..457.........5....6..2....6..98.5..8..2....4.2..9...8.3.......5..8..4
+        let grid = SudokuGrid::from_candidates(
+            "
+            5  2 7  8 9   6  14 34  13
+            6  3 9  1 4   5  7  8   2
+            8  1 4  7 2   3  6  5   9
+            4  5 13 6 8   17 2  9   37
+            2  7 6  4 13  9  8  13  5
+            13 9 8  5 137 2  14 347 6
+            37 4 13 2 5   17 9  6   8
+            9  8 2  3 6   4  5  17  17
+            17 6 5  9 17  8  3  2   4
+            ",
+        );
+        assert_eq!(
+            grid.find_xy_wing(),
+            Some(Deduction {
+                technique: Technique::XYWing,
+                pattern: Pattern::Wing {
+                    pivot: vec![(0, 7)],
+                    pincers: vec![(0, 6), (4, 7)],
+                    digit: 1,
+                },
+                eliminations: vec![Candidate::new(5, 6, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn xyz_wing() {
+        //Partway through solving
+        //........2.2....48..16.2..7..8..7....3....5...6.5.....9..84...65...9......3.7.2.9.
+        let grid = SudokuGrid::from_candidates(
+            "
+            8 5  7 36  4 36  9   1   2
+            9 2  3 5   1 7   4   8   6
+            4 1  6 8   2 9   5   7   3
+            2 8  9 136 7 346 36  5   14
+            3 47 1 26  9 5   678 24  478
+            6 47 5 123 8 34  137 234 9
+            7 9  8 4   3 1   2   6   5
+            1 6  2 9   5 8   37  34  47
+            5 3  4 7   6 2   18  9   18
+            ",
+        );
+        assert_eq!(
+            grid.find_xyz_wing(),
+            Some(Deduction {
+                technique: Technique::XYZWing,
+                pattern: Pattern::Wing {
+                    pivot: vec![(3, 5)],
+                    pincers: vec![(3, 6), (5, 5)],
+                    digit: 3,
+                },
+                eliminations: vec![Candidate::new(3, 3, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn w_wing() {
+        //Partway through solving
+        //4.....9...9.......1.2....5........2.....5..73..167.4.9.634..2......827......3...5
+        let grid = SudokuGrid::from_candidates(
+            "
+            4  38  56  13  2 58 9  16 7
+            36 9   567 137 4 58 38 16 2
+            1  378 2   37  6 9  38 5  4
+            36 37  67  8   9 4  5  2  1
+            8  4   9   2   5 1  6  7  3
+            2  5   1   6   7 3  4  8  9
+            5  6   3   4   1 7  2  9  8
+            9  1   4   5   8 2  7  3  6
+            7  2   8   9   3 6  1  4  5
+            ",
+        );
+        assert_eq!(
+            grid.find_w_wing(),
+            Some(Deduction {
+                technique: Technique::WWing,
+                pattern: Pattern::Wing {
+                    pivot: vec![(2, 1), (2, 6)],
+                    pincers: vec![(0, 1), (1, 6)],
+                    digit: 3,
+                },
+                eliminations: vec![Candidate::new(1, 0, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn xy_wing_on_an_open_grid() {
+        //r1c1 is 1 or 2. If it is 1, r1c5 is 3, and if it is 2, r5c1 is 3. r5c5 sees both.
+        let grid = SudokuGrid::from_candidates(
+            "
+            12 . . . 13 . . . .
+            .  . . . .  . . . .
+            .  . . . .  . . . .
+            .  . . . .  . . . .
+            23 . . . .  . . . .
+            .  . . . .  . . . .
+            .  . . . .  . . . .
+            .  . . . .  . . . .
+            .  . . . .  . . . .
+            ",
+        );
+        assert_eq!(
+            grid.find_xy_wing(),
+            Some(Deduction {
+                technique: Technique::XYWing,
+                pattern: Pattern::Wing {
+                    pivot: vec![(0, 0)],
+                    pincers: vec![(0, 4), (4, 0)],
+                    digit: 3,
+                },
+                eliminations: vec![Candidate::new(4, 4, 3)],
+            })
+        );
+    }
+
+    #[test]
+    fn nothing_without_cells_of_two_values() {
+        let grid = SudokuGrid::new();
+        assert_eq!(grid.find_xy_wing(), None);
+        assert_eq!(grid.find_xyz_wing(), None);
+        assert_eq!(grid.find_w_wing(), None);
+    }
+}